@@ -1,26 +1,85 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Addr,
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Addr, Timestamp,
+    Event, BankMsg, Coin, Order, Uint128, Storage, Reply, SubMsg, SubMsgResult, WasmMsg,
+    from_json, QueryRequest, WasmQuery, CosmosMsg,
 };
 use cw2::set_contract_version;
 use regex::Regex;
 
 use crate::error::ContractError;
-use crate::helpers::addr_validate;
+use crate::helpers::{addr_validate, generate_tip_key, parse_amount};
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, QueryMsg, ProfileResponse, ProfilesResponse, TipsResponse,
-    TipDetailResponse, StatsResponse, AdminResponse, UsernameAvailableResponse,
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ProfileResponse, ProfilesResponse, TipsResponse,
+    TipDetailResponse, StatsResponse, AdminResponse, UsernameAvailableResponse, VerifierResponse,
+    BlockedResponse, RolesResponse, AdminInfo, AdminsResponse, OwnershipProposalResponse,
+    PauseStatusResponse, SubscriptionTiersResponse, SubscriptionsResponse, StreamResponse,
+    StreamsResponse, GoalInfo, GoalsResponse, CampaignInfo, CampaignResponse, CampaignsResponse,
+    PledgeResponse, ConfigResponse, ConfigUpdate, BatchTipEntry, UnregisteredNameTipsResponse,
+    ScheduledTipResponse, ScheduledTipsResponse, RefundRequestsResponse, TipWallResponse,
+    ReferralInfo, ReferralsResponse, PlatformFeesResponse, Cw721ExecuteMsg, NftMetadata,
+    NftTrait, TipReceiptResponse, ReceiptThresholdResponse, Cw721QueryMsg, Cw721OwnerOfResponse,
+    Cw721ReceiveMsg, ProfileNftHookMsg, ListingResponse, ListingsResponse, OffersResponse,
+    ArchivedProfilesResponse, ProfileManagersResponse, DepositsResponse, TipAllowanceResponse,
+    TipAllowancesResponse,
 };
 use crate::state::{
-    UserProfile, TipRecord, USER_PROFILES, WALLET_TO_USERNAME, TIP_RECORDS, TIPS_SENT,
-    TIPS_RECEIVED, CONTRACT_ADMINS,
+    UserProfile, TipRecord, TipReply, EncryptionKey, EncryptedMessage, TipVisibility, PINNED_TIPS,
+    Verification, ModerationAction, USER_PROFILES, WALLET_TO_USERNAME, WALLET_PROFILES, PROFILE_MANAGERS,
+    TIP_RECORDS, TIPS_SENT, TIPS_RECEIVED, TIPS_RECEIVED_BY_TIME, ADMIN_ROLES, PROFILE_VERIFICATIONS, SUSPENDED_PROFILES,
+    Role, OwnershipProposal, PENDING_OWNERSHIP, PAUSE_STATUS, SubscriptionTier, Subscription,
+    SUBSCRIPTION_TIERS, SUBSCRIPTION_TIER_SEQ, SUBSCRIPTIONS, SUBSCRIPTION_SEQ,
+    CREATOR_SUBSCRIPTIONS, SUBSCRIBER_SUBSCRIPTIONS, TipStream, TIP_STREAMS, TIP_STREAM_SEQ,
+    STREAMS_SENT, STREAMS_RECEIVED, ScheduledTip, SCHEDULED_TIPS, SCHEDULED_TIP_SEQ,
+    SCHEDULED_TIPS_SENT, SCHEDULED_TIPS_RECEIVED, DUE_SCHEDULED_TIPS, FundraisingGoal, GoalStatus, GOALS, GOAL_SEQ, Campaign,
+    CampaignStatus, CAMPAIGNS, CAMPAIGN_SEQ, CREATOR_CAMPAIGNS, CAMPAIGN_PLEDGES, TipStatus,
+    EscrowedTip, CONFIG, ESCROWED_TIPS, PENDING_TIPS, RefundRequest, REFUND_REQUESTS, UnregisteredNameTip, UNREGISTERED_NAME_TIPS,
+    UNREGISTERED_NAME_TIP_SEQ, BlockPolicy, BlockTarget, BlockedEntry,
+    BLOCKLIST, BLOCK_POLICIES, REFERRALS, REFERRAL_EARNINGS, PLATFORM_FEES, RECEIPT_THRESHOLDS,
+    TIP_RECEIPTS, RECEIPT_SEQ, PENDING_RECEIPTS, PROFILE_NFTS, PROFILE_NFT_REISSUES, Listing, LISTINGS, Offer, OFFERS,
+    OFFER_SEQ, Config, ArchivedProfile, ARCHIVED_PROFILES, ARCHIVE_SEQ, DEPOSITS, TipAllowance,
+    TIP_ALLOWANCES, LEGACY_CONTRACT_ADMINS, LEGACY_VERIFIERS,
 };
+use cw_storage_plus::Bound;
 
 // Contract name and version info for migration
 const CONTRACT_NAME: &str = "crates.io:tipping-profiles";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Size limits for published encryption keys and private tip messages
+const MAX_ENCRYPTION_KEY_BYTES: usize = 256;
+const MAX_ENCRYPTED_MESSAGE_BYTES: usize = 2048;
+
+// Reply id of the receipt NFT mint submessage
+const RECEIPT_REPLY_ID: u64 = 1;
+
+// Maximum number of usernames on a tip allowance's recipient list
+const MAX_ALLOWED_RECIPIENTS: usize = 50;
+
+// Maximum number of managers on a single profile
+const MAX_PROFILE_MANAGERS: usize = 10;
+
+// Names shorter than this pay double the registration fee per missing character
+const PREMIUM_NAME_LENGTH: usize = 7;
+
+// Maximum number of tips a creator can pin to their wall
+const MAX_PINNED_TIPS: usize = 5;
+
+// Maximum number of tips in a single batch
+const MAX_BATCH_TIPS: usize = 25;
+
+// Scheduled tips: shortest interval and most executions processed per keeper call
+const MIN_SCHEDULE_INTERVAL_SECONDS: u64 = 60 * 60;
+const MAX_DUE_TIPS_PER_CALL: u32 = 30;
+
+// Cap on tips held for one unregistered username, bounding the work done at registration
+const MAX_UNREGISTERED_NAME_TIPS: usize = 50;
+
+// Cap on tips one wallet can hold for the same unregistered username, so a single
+// sender cannot fill the name's slots with dust
+const MAX_UNREGISTERED_NAME_TIPS_PER_SENDER: usize = 5;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -30,16 +89,20 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     
-    // Set the initial admin
+    // Set the initial admin as owner
     let admin_addr = addr_validate(deps.api, &msg.admin)?;
-    CONTRACT_ADMINS.save(deps.storage, &admin_addr, &true)?;
+    ADMIN_ROLES.save(deps.storage, &admin_addr, &vec![Role::Owner])?;
     
-    // Also set the contract creator as an admin
+    let mut events = vec![role_change_event("grant", &admin_addr, Role::Owner, &info.sender)];
+    
+    // Also set the contract creator as an owner
     if admin_addr != info.sender {
-        CONTRACT_ADMINS.save(deps.storage, &info.sender, &true)?;
+        ADMIN_ROLES.save(deps.storage, &info.sender, &vec![Role::Owner])?;
+        events.push(role_change_event("grant", &info.sender, Role::Owner, &info.sender));
     }
     
     Ok(Response::new()
+        .add_events(events)
         .add_attribute("method", "instantiate")
         .add_attribute("admin", msg.admin)
         .add_attribute("creator", info.sender))
@@ -52,6 +115,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Circuit breaker is checked before any message is handled
+    ensure_not_paused(deps.as_ref(), &msg)?;
+    
     match msg {
         ExecuteMsg::RegisterProfile {
             username,
@@ -61,6 +127,7 @@ pub fn execute(
             banner_image,
             twitter,
             website,
+            referrer,
         } => execute_register_profile(
             deps, 
             env, 
@@ -72,6 +139,7 @@ pub fn execute(
             banner_image, 
             twitter, 
             website,
+            referrer,
         ),
         
         ExecuteMsg::UpdateProfile {
@@ -95,20 +163,383 @@ pub fn execute(
             website,
         ),
         
+        ExecuteMsg::SetPrimaryProfile { username } => execute_set_primary_profile(deps, info, username),
+        
+        ExecuteMsg::AddProfileManager { username, manager } => {
+            execute_add_profile_manager(deps, info, username, manager)
+        }
+        
+        ExecuteMsg::RemoveProfileManager { username, manager } => {
+            execute_remove_profile_manager(deps, info, username, manager)
+        }
+        
+        ExecuteMsg::RenewUsername { username } => execute_renew_username(deps, env, info, username),
+        
+        ExecuteMsg::SetEncryptionKey {
+            encryption_key,
+            username,
+        } => execute_set_encryption_key(deps, env, info, encryption_key, username),
+        
         ExecuteMsg::RecordTip {
             to_username,
             amount,
             message,
-        } => execute_record_tip(deps, env, info, to_username, amount, message),
+            goal_id,
+            escrow,
+            encrypted_message,
+            creator_only,
+        } => execute_record_tip(
+            deps,
+            env,
+            info,
+            to_username,
+            amount,
+            message,
+            goal_id,
+            escrow,
+            encrypted_message,
+            creator_only,
+        ),
+        
+        ExecuteMsg::SetTipPrivacy {
+            tip_id,
+            creator_only,
+            username,
+        } => execute_set_tip_privacy(deps, info, tip_id, creator_only, username),
+        
+        ExecuteMsg::SetTipDisplay {
+            tip_id,
+            hidden,
+            pinned,
+        } => execute_set_tip_display(deps, info, tip_id, hidden, pinned),
+        
+        ExecuteMsg::BatchTip { tips, username } => execute_batch_tip(deps, env, info, tips, username),
+        
+        ExecuteMsg::ClaimTips {
+            tip_ids,
+            limit,
+            username,
+        } => execute_claim_tips(deps, env, info, tip_ids, limit, username),
+        
+        ExecuteMsg::ReclaimTip { tip_id } => execute_reclaim_tip(deps, env, info, tip_id),
+        
+        ExecuteMsg::RefundTip { tip_id } => execute_refund_tip(deps, info, tip_id),
+        
+        ExecuteMsg::ReplyToTip { tip_id, message } => execute_reply_to_tip(deps, env, info, tip_id, message),
+        
+        ExecuteMsg::RequestRefund {
+            tip_id,
+            reason,
+            username,
+        } => execute_request_refund(deps, env, info, tip_id, reason, username),
+        
+        ExecuteMsg::ReclaimUnregisteredTip { username, tip_id } => {
+            execute_reclaim_unregistered_tip(deps, env, info, username, tip_id)
+        }
+        
+        ExecuteMsg::CreateGoal {
+            description,
+            target,
+            deadline,
+            username,
+        } => execute_create_goal(deps, env, info, description, target, deadline, username),
+        
+        ExecuteMsg::CloseGoal { goal_id, username } => execute_close_goal(deps, env, info, goal_id, username),
+        
+        ExecuteMsg::AddAdmin { admin, roles } => execute_add_admin(deps, info, admin, roles),
+        
+        ExecuteMsg::RemoveAdmin { admin, roles } => execute_remove_admin(deps, info, admin, roles),
+        
+        ExecuteMsg::RenounceAdmin {} => execute_renounce_admin(deps, info),
+        
+        ExecuteMsg::AddVerifier { verifier } => {
+            execute_add_admin(deps, info, verifier, vec![Role::Verifier])
+        }
+        
+        ExecuteMsg::RemoveVerifier { verifier } => {
+            execute_remove_admin(deps, info, verifier, Some(vec![Role::Verifier]))
+        }
+        
+        ExecuteMsg::WithdrawFees { recipient } => execute_withdraw_fees(deps, info, recipient),
+        
+        ExecuteMsg::ProposeOwnership { new_owner } => {
+            execute_propose_ownership(deps, env, info, new_owner)
+        }
+        
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, info),
+        
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
+        
+        ExecuteMsg::CreateSubscriptionTier {
+            name,
+            price,
+            period_seconds,
+            perks,
+            username,
+        } => execute_create_subscription_tier(deps, env, info, name, price, period_seconds, perks, username),
+        
+        ExecuteMsg::DeactivateSubscriptionTier { tier_id, username } => {
+            execute_deactivate_subscription_tier(deps, info, tier_id, username)
+        }
+        
+        ExecuteMsg::Subscribe {
+            creator,
+            tier_id,
+            periods,
+        } => execute_subscribe(deps, env, info, creator, tier_id, periods),
+        
+        ExecuteMsg::CancelSubscription { subscription_id } => {
+            execute_cancel_subscription(deps, env, info, subscription_id)
+        }
+        
+        ExecuteMsg::ClaimSubscriptions {
+            start_after,
+            limit,
+            username,
+        } => execute_claim_subscriptions(deps, env, info, start_after, limit, username),
+        
+        ExecuteMsg::CreateCampaign {
+            description,
+            target,
+            deadline,
+            username,
+        } => execute_create_campaign(deps, env, info, description, target, deadline, username),
+        
+        ExecuteMsg::Pledge { campaign_id } => execute_pledge(deps, env, info, campaign_id),
+        
+        ExecuteMsg::ClaimCampaignFunds { campaign_id } => {
+            execute_claim_campaign_funds(deps, env, info, campaign_id)
+        }
+        
+        ExecuteMsg::ClaimRefund { campaign_id } => execute_claim_refund(deps, env, info, campaign_id),
+        
+        ExecuteMsg::CreateStream {
+            to_username,
+            duration_seconds,
+            message,
+            username,
+        } => execute_create_stream(deps, env, info, to_username, duration_seconds, message, username),
+        
+        ExecuteMsg::WithdrawStream { stream_id } => execute_withdraw_stream(deps, env, info, stream_id),
+        
+        ExecuteMsg::CancelStream { stream_id } => execute_cancel_stream(deps, env, info, stream_id),
+        
+        ExecuteMsg::CreateScheduledTip {
+            to_username,
+            amount,
+            interval_seconds,
+            start_at,
+            message,
+            username,
+        } => execute_create_scheduled_tip(
+            deps,
+            env,
+            info,
+            to_username,
+            amount,
+            interval_seconds,
+            start_at,
+            message,
+            username,
+        ),
+        
+        ExecuteMsg::CancelScheduledTip { schedule_id } => {
+            execute_cancel_scheduled_tip(deps, env, info, schedule_id)
+        }
+        
+        ExecuteMsg::ExecuteDueTips { limit } => execute_due_tips(deps, env, info, limit),
+        
+        ExecuteMsg::UpdateConfig(update) => execute_update_config(deps, info, update),
+        
+        ExecuteMsg::SetPauseStatus {
+            registrations,
+            profile_updates,
+            tips,
+        } => execute_set_pause_status(deps, info, registrations, profile_updates, tips),
+        
+        ExecuteMsg::VerifyProfile {
+            username,
+            category,
+            expires_at,
+        } => execute_verify_profile(deps, env, info, username, category, expires_at),
+        
+        ExecuteMsg::RevokeVerification { username } => {
+            execute_revoke_verification(deps, info, username)
+        }
+        
+        ExecuteMsg::SuspendProfile { username, reason } => {
+            execute_suspend_profile(deps, env, info, username, reason)
+        }
+        
+        ExecuteMsg::UnsuspendProfile { username, reason } => {
+            execute_unsuspend_profile(deps, info, username, reason)
+        }
+        
+        ExecuteMsg::RedactTipReply { tip_id, reason } => execute_redact_tip_reply(deps, env, info, tip_id, reason),
+        
+        ExecuteMsg::RedactTipMessage {
+            from_username,
+            to_username,
+            timestamp,
+            reason,
+        } => execute_redact_tip_message(deps, env, info, from_username, to_username, timestamp, reason),
+        
+        ExecuteMsg::Block { target, username } => execute_block(deps, env, info, target, username),
+        
+        ExecuteMsg::Unblock { target, username } => execute_unblock(deps, info, target, username),
+        
+        ExecuteMsg::SetBlockPolicy { policy, username } => {
+            execute_set_block_policy(deps, info, policy, username)
+        }
+        
+        ExecuteMsg::ReceiveNft(receive) => execute_receive_nft(deps, env, info, receive),
+        
+        ExecuteMsg::ClaimProfileNft { username } => execute_claim_profile_nft(deps, info, username),
+        
+        ExecuteMsg::SyncProfileOwner { username } => execute_sync_profile_owner(deps, env, username),
+        
+        ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+        
+        ExecuteMsg::WithdrawDeposit { amount } => execute_withdraw_deposit(deps, info, amount),
+        
+        ExecuteMsg::GrantTipAllowance {
+            delegate,
+            spend_limit,
+            period_seconds,
+            allowed_recipients,
+            expires_at,
+        } => execute_grant_tip_allowance(
+            deps,
+            env,
+            info,
+            delegate,
+            spend_limit,
+            period_seconds,
+            allowed_recipients,
+            expires_at,
+        ),
+        
+        ExecuteMsg::RevokeTipAllowance { delegate } => execute_revoke_tip_allowance(deps, info, delegate),
+        
+        ExecuteMsg::TipOnBehalf {
+            from_username,
+            to_username,
+            amount,
+            message,
+        } => execute_tip_on_behalf(deps, env, info, from_username, to_username, amount, message),
+        
+        ExecuteMsg::ListUsername {
+            price,
+            keep_profile_data,
+            username,
+        } => execute_list_username(deps, env, info, price, keep_profile_data, username),
+        
+        ExecuteMsg::CancelListing { username } => execute_cancel_listing(deps, info, username),
+        
+        ExecuteMsg::BuyUsername { username } => execute_buy_username(deps, env, info, username),
         
-        ExecuteMsg::AddAdmin { admin } => execute_add_admin(deps, info, admin),
+        ExecuteMsg::MakeOffer { username } => execute_make_offer(deps, env, info, username),
         
-        ExecuteMsg::RemoveAdmin { admin } => execute_remove_admin(deps, info, admin),
+        ExecuteMsg::WithdrawOffer { username, offer_id } => {
+            execute_withdraw_offer(deps, info, username, offer_id)
+        }
+        
+        ExecuteMsg::AcceptOffer {
+            offer_id,
+            keep_profile_data,
+            username,
+        } => execute_accept_offer(deps, env, info, offer_id, keep_profile_data, username),
+        
+        ExecuteMsg::SetReceiptThreshold { threshold, username } => {
+            execute_set_receipt_threshold(deps, info, threshold, username)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    
+    // Legacy admins could do everything, so they become owners; legacy verifiers keep badge rights
+    let legacy = [
+        (LEGACY_CONTRACT_ADMINS, Role::Owner),
+        (LEGACY_VERIFIERS, Role::Verifier),
+    ];
+    let mut events = Vec::new();
+    for (map, role) in legacy {
+        let addrs = map
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for addr in addrs {
+            map.remove(deps.storage, &addr);
+            let mut roles = ADMIN_ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+            if !roles.contains(&role) {
+                roles.push(role);
+                roles.sort();
+                ADMIN_ROLES.save(deps.storage, &addr, &roles)?;
+                events.push(role_change_event("grant", &addr, role, &env.contract.address));
+            }
+        }
+    }
+    
+    // Index tips stored before the tip wall was ordered by time
+    let received = TIPS_RECEIVED
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (username, tip_refs) in received {
+        for (_, tip_key) in tip_refs {
+            let tip = TIP_RECORDS.load(deps.storage, &tip_key)?;
+            TIPS_RECEIVED_BY_TIME.save(deps.storage, (&username, tip.timestamp.nanos(), &tip_key), &())?;
+        }
+    }
+    
+    // Index active schedules created before they were indexed by recipient
+    let schedules = SCHEDULED_TIPS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (schedule_id, schedule) in schedules {
+        if schedule.ended_at.is_none() {
+            SCHEDULED_TIPS_RECEIVED.save(deps.storage, (&schedule.to_username, schedule_id), &true)?;
+        }
+    }
+    
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_events(events))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        RECEIPT_REPLY_ID => reply_receipt_minted(deps, msg.result),
+        id => Err(ContractError::CustomError {
+            message: format!("Unknown reply id {}", id),
+        }),
+    }
+}
+
+// A failed mint never fails the tip itself; the receipt is simply not recorded
+fn reply_receipt_minted(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let (tip_id, token_id) = PENDING_RECEIPTS
+        .pop_front(deps.storage)?
+        .ok_or_else(|| StdError::not_found("pending receipt"))?;
+    
+    let response = Response::new()
+        .add_attribute("method", "receipt_minted")
+        .add_attribute("tip_id", tip_id.clone());
+    match result {
+        SubMsgResult::Ok(_) => {
+            TIP_RECEIPTS.save(deps.storage, &tip_id, &token_id)?;
+            Ok(response.add_attribute("token_id", token_id))
+        }
+        SubMsgResult::Err(error) => Ok(response.add_attribute("receipt_error", error)),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_register_profile(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     username: String,
@@ -118,19 +549,45 @@ fn execute_register_profile(
     banner_image: Option<String>,
     twitter: Option<String>,
     website: Option<String>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
     // Validate username format
     validate_username(&username)?;
     
-    // Check if username is already taken
-    if USER_PROFILES.has(deps.storage, &username) {
-        return Err(ContractError::UsernameExists { username });
+    // Check if username is already taken; a lapsed registration is archived to free the name
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let mut archived_token = None;
+    let mut settlement_msgs = Vec::new();
+    if let Some(existing) = USER_PROFILES.may_load(deps.storage, &username)? {
+        if !registration_lapsed(&config, &existing, env.block.time) {
+            return Err(ContractError::UsernameExists { username });
+        }
+        let previous_owner = profile_owner(deps.as_ref(), &existing)?;
+        let (token, msgs) = archive_lapsed_profile(deps.branch(), &env, existing, &previous_owner)?;
+        archived_token = Some(token);
+        settlement_msgs = msgs;
+    }
+    
+    // The referrer must be an existing profile held by another wallet
+    if let Some(referrer) = &referrer {
+        let referrer_profile = USER_PROFILES
+            .may_load(deps.storage, referrer)?
+            .ok_or_else(|| ContractError::UsernameNotFound { username: referrer.clone() })?;
+        if referrer_profile.wallet_address == info.sender
+            || WALLET_PROFILES.has(deps.storage, (&info.sender, referrer))
+            || profile_owner(deps.as_ref(), &referrer_profile)? == info.sender
+        {
+            return Err(ContractError::SelfReferral {});
+        }
     }
     
-    // Check if the wallet is already registered
-    if WALLET_TO_USERNAME.has(deps.storage, &info.sender) {
-        let existing_username = WALLET_TO_USERNAME.load(deps.storage, &info.sender)?;
-        return Err(ContractError::WalletAlreadyRegistered { username: existing_username });
+    // Short names cost more to register
+    let fee = registration_fee(&config, &username)?;
+    if let Some(fee) = &fee {
+        ensure_exact_funds(&info, fee)?;
+        let mut fees = PLATFORM_FEES.may_load(deps.storage)?.unwrap_or_default();
+        add_coin(&mut fees, fee.clone());
+        PLATFORM_FEES.save(deps.storage, &fees)?;
     }
     
     // Create and save the new profile
@@ -145,17 +602,180 @@ fn execute_register_profile(
         wallet_address: info.sender.clone(),
         created_at: env.block.time,
         updated_at: env.block.time,
+        encryption_key: None,
+        referrer,
+        expires_at: registration_expiry(&config, env.block.time),
     };
     
     USER_PROFILES.save(deps.storage, &username, &profile)?;
+    attach_profile(deps.storage, &info.sender, &username)?;
+    if let Some(referrer) = &profile.referrer {
+        REFERRALS.save(deps.storage, (referrer, &username), &true)?;
+    }
+    
+    // Release any tips that were waiting for this username
+    let (released, payouts, referral_msgs) =
+        release_unregistered_name_tips(deps.branch(), env.block.time, &profile)?;
+    
+    let mut response = Response::new()
+        .add_attribute("method", "register_profile")
+        .add_attribute("username", username)
+        .add_attribute("wallet", info.sender.clone())
+        .add_attribute("released_tips", released.to_string())
+        .add_messages(settlement_msgs)
+        .add_messages(referral_msgs);
+    if let Some(referrer) = &profile.referrer {
+        response = response.add_attribute("referrer", referrer);
+    }
+    if let Some(fee) = fee {
+        response = response.add_attribute("registration_fee", fee.to_string());
+    }
+    if let Some(expires_at) = profile.expires_at {
+        response = response.add_attribute("expires_at", expires_at.seconds().to_string());
+    }
+    
+    // With profile NFTs enabled the new owner receives the username token. A token escrowed
+    // for a listing of the lapsed registration is handed over; one held elsewhere is stale
+    // and a fresh token is minted under the next reissue id.
+    match archived_token {
+        Some(ArchivedToken::Escrowed) => {
+            response = response.add_message(profile_nft_transfer(deps.storage, &profile.username, &info.sender)?);
+        }
+        Some(ArchivedToken::Stale) | Some(ArchivedToken::None) | None => {
+            if let Some(mint) = profile_nft_mint(deps.storage, &profile)? {
+                response = response.add_message(mint);
+            }
+        }
+    }
+    if !payouts.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: payouts,
+        });
+    }
+    
+    Ok(response)
+}
+
+fn execute_set_primary_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+) -> Result<Response, ContractError> {
+    let profile = USER_PROFILES
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: username.clone() })?;
+    if profile_owner(deps.as_ref(), &profile)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    
     WALLET_TO_USERNAME.save(deps.storage, &info.sender, &username)?;
     
     Ok(Response::new()
-        .add_attribute("method", "register_profile")
+        .add_attribute("method", "set_primary_profile")
+        .add_attribute("wallet", info.sender)
+        .add_attribute("username", username))
+}
+
+fn execute_add_profile_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+    manager: String,
+) -> Result<Response, ContractError> {
+    let profile = USER_PROFILES
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: username.clone() })?;
+    if profile_owner(deps.as_ref(), &profile)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let manager = addr_validate(deps.api, &manager)?;
+    let managers = PROFILE_MANAGERS
+        .prefix(&username)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if managers >= MAX_PROFILE_MANAGERS {
+        return Err(ContractError::TooManyManagers {
+            max: MAX_PROFILE_MANAGERS,
+        });
+    }
+    PROFILE_MANAGERS.save(deps.storage, (&username, &manager), &true)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "add_profile_manager")
+        .add_attribute("username", username)
+        .add_attribute("manager", manager))
+}
+
+fn execute_remove_profile_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+    manager: String,
+) -> Result<Response, ContractError> {
+    let profile = USER_PROFILES
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: username.clone() })?;
+    let manager = addr_validate(deps.api, &manager)?;
+    if profile_owner(deps.as_ref(), &profile)? != info.sender && manager != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    PROFILE_MANAGERS.remove(deps.storage, (&username, &manager));
+    
+    Ok(Response::new()
+        .add_attribute("method", "remove_profile_manager")
+        .add_attribute("username", username)
+        .add_attribute("manager", manager))
+}
+
+fn execute_renew_username(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+) -> Result<Response, ContractError> {
+    let mut profile = USER_PROFILES
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: username.clone() })?;
+    if profile_owner(deps.as_ref(), &profile)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let expires_at = match profile.expires_at {
+        Some(expires_at) if config.registration_period_seconds > 0 => expires_at,
+        _ => return Err(ContractError::RenewalNotRequired { username }),
+    };
+    if registration_lapsed(&config, &profile, env.block.time) {
+        return Err(ContractError::UsernameExpired { username });
+    }
+    
+    let fee = registration_fee(&config, &username)?;
+    if let Some(fee) = &fee {
+        ensure_exact_funds(&info, fee)?;
+        let mut fees = PLATFORM_FEES.may_load(deps.storage)?.unwrap_or_default();
+        add_coin(&mut fees, fee.clone());
+        PLATFORM_FEES.save(deps.storage, &fees)?;
+    }
+    
+    // Renewals extend from the current expiry, so renewing early loses nothing
+    let expires_at = expires_at.plus_seconds(config.registration_period_seconds);
+    profile.expires_at = Some(expires_at);
+    USER_PROFILES.save(deps.storage, &username, &profile)?;
+    
+    let mut response = Response::new()
+        .add_attribute("method", "renew_username")
         .add_attribute("username", username)
-        .add_attribute("wallet", info.sender))
+        .add_attribute("expires_at", expires_at.seconds().to_string());
+    if let Some(fee) = fee {
+        response = response.add_attribute("registration_fee", fee.to_string());
+    }
+    
+    Ok(response)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_update_profile(
     deps: DepsMut,
     env: Env,
@@ -176,8 +796,11 @@ fn execute_update_profile(
     // Load the profile
     let mut profile = USER_PROFILES.load(deps.storage, &username)?;
     
-    // Check authorization - only the owner or an admin can update the profile
-    if profile.wallet_address != info.sender && !is_admin(deps.as_ref(), &info.sender)? {
+    // Check authorization - only the owner, a manager or a moderator can update the profile
+    if profile_owner(deps.as_ref(), &profile)? != info.sender
+        && !PROFILE_MANAGERS.has(deps.storage, (&username, &info.sender))
+        && !has_role(deps.as_ref(), &info.sender, Role::Moderator)?
+    {
         return Err(ContractError::Unauthorized {});
     }
     
@@ -216,365 +839,4805 @@ fn execute_update_profile(
         .add_attribute("username", username))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_record_tip(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     to_username: String,
     amount: String,
     message: Option<String>,
+    goal_id: Option<u64>,
+    escrow: Option<bool>,
+    encrypted_message: Option<EncryptedMessage>,
+    creator_only: Option<bool>,
 ) -> Result<Response, ContractError> {
     // Get sender's username
-    let from_username = match WALLET_TO_USERNAME.may_load(deps.storage, &info.sender)? {
-        Some(username) => username,
-        None => return Err(ContractError::CustomError {
-            message: "Sender wallet address not registered with a profile".to_string(),
-        }),
-    };
+    let from_username = load_sender_username(deps.as_ref(), &info.sender)?;
     
     // Check that sender is not tipping themselves
     if from_username == to_username {
         return Err(ContractError::SelfTipping {});
     }
     
-    // Check if recipient exists
+    // Suspended profiles can neither send nor receive tips
+    ensure_not_suspended(deps.as_ref(), &from_username)?;
+    
+    // Check if recipient exists; funded tips to a free username wait for its registration
     if !USER_PROFILES.has(deps.storage, &to_username) {
-        return Err(ContractError::UsernameNotFound { username: to_username });
+        if info.funds.is_empty() || goal_id.is_some() {
+            return Err(ContractError::UsernameNotFound { username: to_username });
+        }
+        if encrypted_message.is_some() {
+            return Err(ContractError::EncryptionKeyNotPublished { username: to_username });
+        }
+        return hold_tip_for_unregistered_name(
+            deps, env, info, from_username, to_username, amount, message,
+        );
+    }
+    ensure_not_suspended(deps.as_ref(), &to_username)?;
+    let sender = info.sender.clone();
+    
+    // Private messages must target the key the recipient currently publishes
+    if let Some(encrypted) = &encrypted_message {
+        validate_encrypted_message(deps.as_ref(), &to_username, encrypted)?;
+    }
+    
+    // Apply the recipient's blocklist
+    let mut message = message;
+    let mut encrypted_message = encrypted_message;
+    let mut message_dropped = false;
+    if is_blocked(deps.as_ref(), &to_username, Some(&from_username), &info.sender)? {
+        match BLOCK_POLICIES.may_load(deps.storage, &to_username)?.unwrap_or_default() {
+            BlockPolicy::RejectTip => {
+                return Err(ContractError::SenderBlocked { username: to_username });
+            }
+            BlockPolicy::DropMessage => {
+                message_dropped = message.is_some() || encrypted_message.is_some();
+                message = None;
+                encrypted_message = None;
+            }
+        }
+    }
+    
+    // Attached funds must match the declared amount
+    let funds = tip_funds(&info, &amount)?;
+    let escrow = escrow.unwrap_or(false);
+    if escrow && funds.is_none() {
+        return Err(ContractError::InvalidFunds {
+            reason: "escrowed tips must attach the tip amount".to_string(),
+        });
+    }
+    
+    // Count an earmarked tip towards the recipient's goal; only attached funds count
+    if let Some(goal_id) = goal_id {
+        let mut goal = GOALS
+            .may_load(deps.storage, (&to_username, goal_id))?
+            .ok_or(ContractError::GoalNotFound { goal_id })?;
+        if !goal.accepts_tips(env.block.time) {
+            return Err(ContractError::GoalNotActive { goal_id });
+        }
+        
+        let funds = funds.as_ref().ok_or_else(|| ContractError::InvalidFunds {
+            reason: "tips towards a goal must attach the tip amount".to_string(),
+        })?;
+        if funds.denom != goal.denom {
+            return Err(ContractError::InvalidAmount { amount });
+        }
+        
+        goal.raised += funds.amount;
+        if goal.completed_at.is_none() && goal.raised >= goal.target {
+            goal.completed_at = Some(env.block.time);
+        }
+        GOALS.save(deps.storage, (&to_username, goal_id), &goal)?;
     }
     
-    // Create tip record
-    let timestamp = env.block.time;
+    // Funds forwarded straight to the recipient pay the platform fee now, escrowed ones on claim
+    let recipient = USER_PROFILES.load(deps.storage, &to_username)?;
+    let mut referral_msg = None;
+    let delivered = match &funds {
+        Some(funds) if !escrow => {
+            let (net, msg) = split_tip_funds(deps.branch(), env.block.time, &recipient, funds.clone())?;
+            referral_msg = msg;
+            Some(net)
+        }
+        _ => None,
+    };
+    
+    // Create and save the tip record
     let tip = TipRecord {
         from_username: from_username.clone(),
         to_username: to_username.clone(),
         amount,
         message,
-        timestamp,
+        timestamp: env.block.time,
+        moderation: None,
+        goal_id,
+        status: if escrow { TipStatus::Pending } else { TipStatus::Completed },
+        reply: None,
+        encrypted_message,
+        visibility: TipVisibility {
+            creator_only: creator_only.unwrap_or(false),
+            ..TipVisibility::default()
+        },
+        paid_by: funds.as_ref().map(|_| info.sender.clone()),
+        delivered: delivered.clone(),
     };
+    let (tip_key, tip) = store_tip(deps.storage, tip)?;
     
-    // Create a composite key
-    let tip_key = format!("{}:{}:{}", from_username, to_username, timestamp.nanos());
-    
-    // Save tip record
-    TIP_RECORDS.save(deps.storage, &tip_key, &tip)?;
+    let mut response = Response::new()
+        .add_attribute("method", "record_tip")
+        .add_attribute("from", from_username)
+        .add_attribute("to", to_username.clone())
+        .add_attribute("timestamp", tip.timestamp.to_string())
+        .add_attribute("tip_id", tip_key.clone())
+        .add_attribute("message_dropped", message_dropped.to_string())
+        .add_attribute("escrow", escrow.to_string());
     
-    // Update tips sent for sender
-    let mut sender_tips = TIPS_SENT.may_load(deps.storage, &from_username)?.unwrap_or_default();
-    sender_tips.push((to_username.clone(), tip_key.clone()));
-    TIPS_SENT.save(deps.storage, &from_username, &sender_tips)?;
+    match (&funds, delivered) {
+        // Hold the funds until the recipient claims them
+        (Some(funds), None) => {
+            let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+            let escrowed = EscrowedTip {
+                sender: info.sender.clone(),
+                funds: funds.clone(),
+                reclaimable_at: env.block.time.plus_seconds(config.escrow_timeout_seconds),
+            };
+            ESCROWED_TIPS.save(deps.storage, &tip_key, &escrowed)?;
+            PENDING_TIPS.save(deps.storage, (&to_username, &tip_key), &true)?;
+        }
+        // Forward the funds straight to the recipient
+        (Some(_), Some(net)) => {
+            response = response
+                .add_message(BankMsg::Send {
+                    to_address: profile_owner(deps.as_ref(), &recipient)?.to_string(),
+                    amount: vec![net],
+                })
+                .add_messages(referral_msg);
+        }
+        _ => {}
+    }
     
-    // Update tips received for recipient
-    let mut recipient_tips = TIPS_RECEIVED.may_load(deps.storage, &to_username)?.unwrap_or_default();
-    recipient_tips.push((from_username.clone(), tip_key));
-    TIPS_RECEIVED.save(deps.storage, &to_username, &recipient_tips)?;
+    // Mint a receipt NFT when the delivered funds reach the recipient's threshold; escrowed
+    // tips earn theirs when claimed and record-only tips never earn one
+    if let (Some(funds), Some(_)) = (&funds, &tip.delivered) {
+        if let Some(receipt) = receipt_submsg(deps, &env, &sender, &tip_key, &tip, funds)? {
+            response = response.add_submessage(receipt);
+        }
+    }
     
-    Ok(Response::new()
-        .add_attribute("method", "record_tip")
-        .add_attribute("from", from_username)
-        .add_attribute("to", to_username)
-        .add_attribute("timestamp", timestamp.to_string()))
+    Ok(response)
 }
 
-fn execute_add_admin(
+fn execute_set_tip_privacy(
     deps: DepsMut,
     info: MessageInfo,
-    admin: String,
+    tip_id: String,
+    creator_only: bool,
+    username: Option<String>,
 ) -> Result<Response, ContractError> {
-    // Check if sender is admin
-    if !is_admin(deps.as_ref(), &info.sender)? {
+    let from_username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let mut tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    if tip.from_username != from_username {
         return Err(ContractError::Unauthorized {});
     }
     
-    // Validate and add new admin
-    let admin_addr = addr_validate(deps.api, &admin)?;
-    CONTRACT_ADMINS.save(deps.storage, &admin_addr, &true)?;
+    // A tip taken off the wall by its sender cannot stay pinned
+    tip.visibility.creator_only = creator_only;
+    if creator_only && tip.visibility.pinned {
+        tip.visibility.pinned = false;
+        PINNED_TIPS.remove(deps.storage, (&tip.to_username, &tip_id));
+    }
+    TIP_RECORDS.save(deps.storage, &tip_id, &tip)?;
     
     Ok(Response::new()
-        .add_attribute("method", "add_admin")
-        .add_attribute("admin", admin))
+        .add_attribute("method", "set_tip_privacy")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("creator_only", creator_only.to_string()))
 }
 
-fn execute_remove_admin(
+fn execute_set_tip_display(
     deps: DepsMut,
     info: MessageInfo,
-    admin: String,
+    tip_id: String,
+    hidden: Option<bool>,
+    pinned: Option<bool>,
 ) -> Result<Response, ContractError> {
-    // Check if sender is admin
-    if !is_admin(deps.as_ref(), &info.sender)? {
+    let mut tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    let recipient = USER_PROFILES.load(deps.storage, &tip.to_username)?;
+    if profile_owner(deps.as_ref(), &recipient)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Tips sent before the name was last registered belong to its previous owner
+    if tip.timestamp < recipient.created_at {
         return Err(ContractError::Unauthorized {});
     }
     
-    // Validate admin address
-    let admin_addr = addr_validate(deps.api, &admin)?;
-    
-    // Prevent removing the last admin
-    let is_last_admin = CONTRACT_ADMINS
-        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .count() <= 1;
-    
-    if is_last_admin {
-        return Err(ContractError::CustomError {
-            message: "Cannot remove the last admin".to_string(),
-        });
+    // Hiding a tip unpins it and pinning a tip unhides it
+    if let Some(hidden) = hidden {
+        tip.visibility.hidden = hidden;
+        if hidden {
+            tip.visibility.pinned = false;
+        }
+    }
+    if let Some(pinned) = pinned {
+        if pinned && tip.visibility.creator_only {
+            return Err(ContractError::TipCreatorOnly { tip_id });
+        }
+        tip.visibility.pinned = pinned;
+        if pinned {
+            tip.visibility.hidden = false;
+        }
     }
     
-    CONTRACT_ADMINS.remove(deps.storage, &admin_addr);
+    if tip.visibility.pinned {
+        if !PINNED_TIPS.has(deps.storage, (&tip.to_username, &tip_id)) {
+            let pinned_count = PINNED_TIPS
+                .prefix(&tip.to_username)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count();
+            if pinned_count >= MAX_PINNED_TIPS {
+                return Err(ContractError::TooManyPinnedTips { max: MAX_PINNED_TIPS });
+            }
+            PINNED_TIPS.save(deps.storage, (&tip.to_username, &tip_id), &true)?;
+        }
+    } else {
+        PINNED_TIPS.remove(deps.storage, (&tip.to_username, &tip_id));
+    }
+    TIP_RECORDS.save(deps.storage, &tip_id, &tip)?;
     
     Ok(Response::new()
-        .add_attribute("method", "remove_admin")
-        .add_attribute("admin", admin))
+        .add_attribute("method", "set_tip_display")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("hidden", tip.visibility.hidden.to_string())
+        .add_attribute("pinned", tip.visibility.pinned.to_string()))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetProfile { username } => to_json_binary(&query_profile(deps, username)?),
-        QueryMsg::GetProfileByWallet { wallet } => to_json_binary(&query_profile_by_wallet(deps, wallet)?),
-        QueryMsg::ListProfiles { limit, start_after } => to_json_binary(&query_list_profiles(deps, limit, start_after)?),
-        QueryMsg::GetTipsSent { username, limit, start_after } => to_json_binary(&query_tips_sent(deps, username, limit, start_after)?),
-        QueryMsg::GetTipsReceived { username, limit, start_after } => to_json_binary(&query_tips_received(deps, username, limit, start_after)?),
-        QueryMsg::GetTipDetail { from_username, to_username, timestamp } => to_json_binary(&query_tip_detail(deps, from_username, to_username, timestamp)?),
-        QueryMsg::GetUserStats { username } => to_json_binary(&query_user_stats(deps, username)?),
-        QueryMsg::IsAdmin { address } => to_json_binary(&query_is_admin(deps, address)?),
-        QueryMsg::IsUsernameAvailable { username } => to_json_binary(&query_is_username_available(deps, username)?),
+fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // Only the configured profile collection can hand tokens to this contract
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if config.profile_nft_contract.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
     }
-}
-
-fn query_profile(deps: Deps, username: String) -> StdResult<ProfileResponse> {
-    let profile = USER_PROFILES.may_load(deps.storage, &username)?;
-    Ok(ProfileResponse { profile })
-}
-
-fn query_profile_by_wallet(deps: Deps, wallet: String) -> StdResult<ProfileResponse> {
-    let addr = deps.api.addr_validate(&wallet)?;
     
-    // Get username associated with this wallet
-    let username = match WALLET_TO_USERNAME.may_load(deps.storage, &addr)? {
-        Some(username) => username,
-        None => return Ok(ProfileResponse { profile: None }),
-    };
+    // Stale tokens from a lapsed registration no longer stand for the username
+    let username = receive.token_id.split('#').next().unwrap_or_default().to_string();
+    if !PROFILE_NFTS.has(deps.storage, &username)
+        || profile_token_id(deps.storage, &username)? != receive.token_id
+    {
+        return Err(ContractError::UsernameNotFound { username });
+    }
+    ensure_not_suspended(deps.as_ref(), &username)?;
     
-    let profile = USER_PROFILES.may_load(deps.storage, &username)?;
-    Ok(ProfileResponse { profile })
-}
-
-fn query_list_profiles(
-    deps: Deps,
-    limit: Option<u32>,
-    start_after: Option<String>,
-) -> StdResult<ProfilesResponse> {
-    let limit = limit.unwrap_or(30) as usize;
+    // The token was sent by its owner
+    let nft_owner = addr_validate(deps.api, &receive.sender)?;
+    let recipient = match from_json(&receive.msg)? {
+        ProfileNftHookMsg::TransferProfile { recipient } => recipient,
+        ProfileNftHookMsg::ListProfile {
+            price,
+            keep_profile_data,
+        } => {
+            return list_username(deps, env, nft_owner, username, price, keep_profile_data, true);
+        }
+        ProfileNftHookMsg::AcceptOffer {
+            offer_id,
+            keep_profile_data,
+        } => {
+            return accept_offer(deps, env, nft_owner, username, offer_id, keep_profile_data, true);
+        }
+    };
+    let recipient = addr_validate(deps.api, &recipient)?;
+    let (previous, settlement_msgs) = move_profile(deps, &env, &username, &recipient)?;
     
-    // Get all profiles
-    let all_profiles: StdResult<Vec<UserProfile>> = USER_PROFILES
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|item| {
-            let (_, profile) = item?;
-            Ok(profile)
-        })
-        .collect();
+    let transfer = Cw721ExecuteMsg::TransferNft {
+        recipient: recipient.to_string(),
+        token_id: receive.token_id,
+    };
     
-    // Filter and limit profiles
-    let profiles = all_profiles?
-        .into_iter()
-        .filter(|p| if let Some(ref start) = start_after {
-            p.username > *start
-        } else {
-            true
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: info.sender.to_string(),
+            msg: to_json_binary(&transfer)?,
+            funds: vec![],
         })
-        .take(limit)
-        .collect();
-    
-    Ok(ProfilesResponse { profiles })
+        .add_messages(settlement_msgs)
+        .add_attribute("method", "transfer_profile")
+        .add_attribute("username", username)
+        .add_attribute("from", previous)
+        .add_attribute("nft_sender", receive.sender)
+        .add_attribute("to", recipient))
 }
 
-fn query_tips_sent(
-    deps: Deps,
-    username: String,
-    limit: Option<u32>,
-    start_after: Option<String>,
-) -> StdResult<TipsResponse> {
-    // Check if user exists
-    if !USER_PROFILES.has(deps.storage, &username) {
-        return Ok(TipsResponse { tips: Vec::new() });
+fn execute_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.funds.is_empty() || info.funds.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::InvalidFunds {
+            reason: "attach the non-zero coins to deposit".to_string(),
+        });
     }
     
-    let limit = limit.unwrap_or(30) as usize;
-    
-    // Get all tip references for this sender
-    let tip_refs = TIPS_SENT.may_load(deps.storage, &username)?.unwrap_or_default();
-    
-    // Filter by start_after if provided
-    let filtered_refs = if let Some(after_key) = start_after {
-        tip_refs
-            .into_iter()
-            .filter(|(_, key)| key > &after_key)
-            .collect::<Vec<_>>()
-    } else {
-        tip_refs
-    };
-    
-    // Sort by key (which contains timestamp) - newest first
-    let mut sorted_refs = filtered_refs;
-    sorted_refs.sort_by(|a, b| b.1.cmp(&a.1));
-    
-    // Take up to limit
-    let limited_refs = sorted_refs.into_iter().take(limit).collect::<Vec<_>>();
-    
-    // Retrieve the actual tip records
-    let mut tips = Vec::new();
-    for (_, tip_key) in limited_refs {
-        if let Ok(tip) = TIP_RECORDS.load(deps.storage, &tip_key) {
-            tips.push(tip);
-        }
+    let mut balances = DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    for coin in &info.funds {
+        add_coin(&mut balances, coin.clone());
     }
+    DEPOSITS.save(deps.storage, &info.sender, &balances)?;
     
-    Ok(TipsResponse { tips })
+    Ok(Response::new()
+        .add_attribute("method", "deposit")
+        .add_attribute("wallet", info.sender)
+        .add_attribute(
+            "amount",
+            info.funds.iter().map(|coin| coin.to_string()).collect::<Vec<_>>().join(","),
+        ))
 }
 
-fn query_tips_received(
-    deps: Deps,
-    username: String,
-    limit: Option<u32>,
-    start_after: Option<String>,
-) -> StdResult<TipsResponse> {
-    // Check if user exists
-    if !USER_PROFILES.has(deps.storage, &username) {
-        return Ok(TipsResponse { tips: Vec::new() });
-    }
-    
-    let limit = limit.unwrap_or(30) as usize;
+fn execute_withdraw_deposit(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    let mut balances = DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    take_coin(&mut balances, &amount)?;
+    DEPOSITS.save(deps.storage, &info.sender, &balances)?;
     
-    // Get all tip references for this recipient
-    let tip_refs = TIPS_RECEIVED.may_load(deps.storage, &username)?.unwrap_or_default();
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![amount.clone()],
+        })
+        .add_attribute("method", "withdraw_deposit")
+        .add_attribute("wallet", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_grant_tip_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegate: String,
+    spend_limit: Vec<Coin>,
+    period_seconds: u64,
+    allowed_recipients: Option<Vec<String>>,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let delegate = addr_validate(deps.api, &delegate)?;
+    if delegate == info.sender {
+        return Err(ContractError::InvalidTipAllowance {
+            reason: "cannot delegate to yourself".to_string(),
+        });
+    }
+    if period_seconds == 0 {
+        return Err(ContractError::InvalidTipAllowance {
+            reason: "period must be longer than zero seconds".to_string(),
+        });
+    }
+    if spend_limit.is_empty() || spend_limit.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::InvalidTipAllowance {
+            reason: "spend limit must list non-zero coins".to_string(),
+        });
+    }
+    let mut limits: Vec<Coin> = Vec::new();
+    for coin in spend_limit {
+        add_coin(&mut limits, coin);
+    }
+    if let Some(recipients) = &allowed_recipients {
+        if recipients.is_empty() || recipients.len() > MAX_ALLOWED_RECIPIENTS {
+            return Err(ContractError::InvalidTipAllowance {
+                reason: format!("recipient list must name between 1 and {} usernames", MAX_ALLOWED_RECIPIENTS),
+            });
+        }
+        for username in recipients {
+            validate_username(username)?;
+        }
+    }
+    if expires_at.is_some_and(|expires_at| expires_at <= env.block.time) {
+        return Err(ContractError::InvalidTipAllowance {
+            reason: "expiry must be in the future".to_string(),
+        });
+    }
     
-    // Filter by start_after if provided
-    let filtered_refs = if let Some(after_key) = start_after {
-        tip_refs
-            .into_iter()
-            .filter(|(_, key)| key > &after_key)
-            .collect::<Vec<_>>()
-    } else {
-        tip_refs
+    let allowance = TipAllowance {
+        delegate: delegate.clone(),
+        spend_limit: limits,
+        period_seconds,
+        allowed_recipients,
+        expires_at,
+        period_start: env.block.time,
+        spent: Vec::new(),
     };
+    TIP_ALLOWANCES.save(deps.storage, (&info.sender, &delegate), &allowance)?;
     
-    // Sort by key (which contains timestamp) - newest first
-    let mut sorted_refs = filtered_refs;
-    sorted_refs.sort_by(|a, b| b.1.cmp(&a.1));
-    
-    // Take up to limit
-    let limited_refs = sorted_refs.into_iter().take(limit).collect::<Vec<_>>();
-    
-    // Retrieve the actual tip records
-    let mut tips = Vec::new();
-    for (_, tip_key) in limited_refs {
-        if let Ok(tip) = TIP_RECORDS.load(deps.storage, &tip_key) {
-            tips.push(tip);
-        }
+    Ok(Response::new()
+        .add_attribute("method", "grant_tip_allowance")
+        .add_attribute("granter", info.sender)
+        .add_attribute("delegate", delegate)
+        .add_attribute("period_seconds", period_seconds.to_string()))
+}
+
+fn execute_revoke_tip_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let delegate = addr_validate(deps.api, &delegate)?;
+    if !TIP_ALLOWANCES.has(deps.storage, (&info.sender, &delegate)) {
+        return Err(ContractError::TipAllowanceNotFound {
+            delegate: delegate.to_string(),
+        });
     }
+    TIP_ALLOWANCES.remove(deps.storage, (&info.sender, &delegate));
     
-    Ok(TipsResponse { tips })
+    Ok(Response::new()
+        .add_attribute("method", "revoke_tip_allowance")
+        .add_attribute("granter", info.sender)
+        .add_attribute("delegate", delegate))
 }
 
-fn query_tip_detail(
-    deps: Deps,
+fn execute_tip_on_behalf(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     from_username: String,
     to_username: String,
-    timestamp: cosmwasm_std::Timestamp,
-) -> StdResult<TipDetailResponse> {
-    let key = format!("{}:{}:{}", from_username, to_username, timestamp.nanos());
-    let tip = TIP_RECORDS.may_load(deps.storage, &key)?;
-    Ok(TipDetailResponse { tip })
-}
-
-fn query_user_stats(deps: Deps, username: String) -> StdResult<StatsResponse> {
-    // Check if user exists
-    if !USER_PROFILES.has(deps.storage, &username) {
-        return Ok(StatsResponse {
-            total_tips_sent: 0,
-            total_tips_received: 0,
-            total_amount_sent: "0uxion".to_string(),
-            total_amount_received: "0uxion".to_string(),
+    amount: Coin,
+    message: Option<String>,
+) -> Result<Response, ContractError> {
+    let sender_profile = USER_PROFILES
+        .may_load(deps.storage, &from_username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: from_username.clone() })?;
+    let granter = profile_owner(deps.as_ref(), &sender_profile)?;
+    ensure_not_suspended(deps.as_ref(), &from_username)?;
+    
+    let mut allowance = TIP_ALLOWANCES
+        .may_load(deps.storage, (&granter, &info.sender))?
+        .ok_or_else(|| ContractError::TipAllowanceNotFound {
+            delegate: info.sender.to_string(),
+        })?;
+    if allowance.expires_at.is_some_and(|expires_at| env.block.time >= expires_at) {
+        return Err(ContractError::TipAllowanceExceeded {
+            reason: "allowance has expired".to_string(),
         });
     }
     
-    // Get all tips sent
-    let tips_sent = TIPS_SENT.may_load(deps.storage, &username)?.unwrap_or_default();
-    let total_tips_sent = tips_sent.len() as u64;
-    
-    // Get all tips received
-    let tips_received = TIPS_RECEIVED.may_load(deps.storage, &username)?.unwrap_or_default();
-    let total_tips_received = tips_received.len() as u64;
-    
-    // Calculate total amount sent
-    let mut total_amount_sent = 0u128;
-    for (_, tip_key) in &tips_sent {
-        if let Ok(tip) = TIP_RECORDS.load(deps.storage, tip_key) {
-            if let Some(amount_str) = tip.amount.strip_suffix("uxion") {
-                if let Ok(amount) = amount_str.parse::<u128>() {
-                    total_amount_sent += amount;
-                }
-            }
+    if amount.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {
+            amount: amount.to_string(),
+        });
+    }
+    if to_username == from_username {
+        return Err(ContractError::SelfTipping {});
+    }
+    if let Some(recipients) = &allowance.allowed_recipients {
+        if !recipients.contains(&to_username) {
+            return Err(ContractError::TipAllowanceExceeded {
+                reason: format!("'{}' is not an allowed recipient", to_username),
+            });
         }
     }
+    let recipient = USER_PROFILES
+        .may_load(deps.storage, &to_username)?
+        .ok_or_else(|| ContractError::UsernameNotFound { username: to_username.clone() })?;
+    ensure_not_suspended(deps.as_ref(), &to_username)?;
     
-    // Calculate total amount received
-    let mut total_amount_received = 0u128;
-    for (_, tip_key) in &tips_received {
-        if let Ok(tip) = TIP_RECORDS.load(deps.storage, tip_key) {
-            if let Some(amount_str) = tip.amount.strip_suffix("uxion") {
-                if let Ok(amount) = amount_str.parse::<u128>() {
-                    total_amount_received += amount;
-                }
+    let mut message = message;
+    if is_blocked(deps.as_ref(), &to_username, Some(&from_username), &granter)? {
+        match BLOCK_POLICIES.may_load(deps.storage, &to_username)?.unwrap_or_default() {
+            BlockPolicy::RejectTip => {
+                return Err(ContractError::SenderBlocked { username: to_username });
             }
+            BlockPolicy::DropMessage => message = None,
         }
     }
     
-    Ok(StatsResponse {
-        total_tips_sent,
-        total_tips_received,
+    // Spend from the current period's limit, then from the fan's deposit
+    roll_allowance_period(&mut allowance, env.block.time);
+    let limit = allowance
+        .spend_limit
+        .iter()
+        .find(|coin| coin.denom == amount.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    let spent = allowance
+        .spent
+        .iter()
+        .find(|coin| coin.denom == amount.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if spent + amount.amount > limit {
+        return Err(ContractError::TipAllowanceExceeded {
+            reason: format!("{} left this period", Coin::new((limit - spent).u128(), &amount.denom)),
+        });
+    }
+    add_coin(&mut allowance.spent, amount.clone());
+    TIP_ALLOWANCES.save(deps.storage, (&granter, &info.sender), &allowance)?;
+    
+    let mut balances = DEPOSITS.may_load(deps.storage, &granter)?.unwrap_or_default();
+    take_coin(&mut balances, &amount)?;
+    DEPOSITS.save(deps.storage, &granter, &balances)?;
+    
+    let (net, referral_msg) = split_tip_funds(deps.branch(), env.block.time, &recipient, amount.clone())?;
+    let tip = TipRecord {
+        from_username: from_username.clone(),
+        to_username: to_username.clone(),
+        amount: amount.to_string(),
+        message,
+        timestamp: env.block.time,
+        moderation: None,
+        goal_id: None,
+        status: TipStatus::Completed,
+        reply: None,
+        encrypted_message: None,
+        visibility: TipVisibility::default(),
+        paid_by: Some(granter.clone()),
+        delivered: Some(net.clone()),
+    };
+    let (tip_key, _) = store_tip(deps.storage, tip)?;
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: profile_owner(deps.as_ref(), &recipient)?.to_string(),
+            amount: vec![net],
+        })
+        .add_messages(referral_msg)
+        .add_attribute("method", "tip_on_behalf")
+        .add_attribute("tip_id", tip_key)
+        .add_attribute("from", from_username)
+        .add_attribute("to", to_username)
+        .add_attribute("delegate", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_list_username(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price: Coin,
+    keep_profile_data: bool,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    // NFT-backed usernames are listed by sending the token; an escrowed listing can be repriced
+    let nft_escrowed = match LISTINGS.may_load(deps.storage, &username)? {
+        Some(listing) => listing.nft_escrowed,
+        None if PROFILE_NFTS.has(deps.storage, &username) => {
+            return Err(ContractError::ProfileNftRequired { username });
+        }
+        None => false,
+    };
+    ensure_not_suspended(deps.as_ref(), &username)?;
+    
+    list_username(deps, env, info.sender, username, price, keep_profile_data, nft_escrowed)
+}
+
+fn execute_cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let listing = LISTINGS
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotListed { username: username.clone() })?;
+    LISTINGS.remove(deps.storage, &username);
+    
+    let mut response = Response::new()
+        .add_attribute("method", "cancel_listing")
+        .add_attribute("username", username.clone());
+    
+    // Give the escrowed profile NFT back to the seller
+    if listing.nft_escrowed {
+        response = response.add_message(profile_nft_transfer(deps.storage, &username, &listing.seller)?);
+    }
+    
+    Ok(response)
+}
+
+fn execute_buy_username(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+) -> Result<Response, ContractError> {
+    let listing = LISTINGS
+        .may_load(deps.storage, &username)?
+        .ok_or_else(|| ContractError::UsernameNotListed { username: username.clone() })?;
+    ensure_exact_funds(&info, &listing.price)?;
+    
+    let msgs = complete_username_sale(
+        deps,
+        &env,
+        &username,
+        &info.sender,
+        listing.price.clone(),
+        listing.keep_profile_data,
+        listing.nft_escrowed,
+    )?;
+    
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "buy_username")
+        .add_attribute("username", username)
+        .add_attribute("seller", listing.seller)
+        .add_attribute("buyer", info.sender)
+        .add_attribute("price", listing.price.to_string()))
+}
+
+fn execute_make_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+) -> Result<Response, ContractError> {
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Err(ContractError::UsernameNotFound { username });
+    }
+    let price = match info.funds.as_slice() {
+        [coin] if !coin.amount.is_zero() => coin.clone(),
+        _ => {
+            return Err(ContractError::InvalidFunds {
+                reason: "attach exactly one non-zero coin".to_string(),
+            })
+        }
+    };
+    
+    let offer_id = OFFER_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    OFFER_SEQ.save(deps.storage, &offer_id)?;
+    
+    let offer = Offer {
+        id: offer_id,
+        username: username.clone(),
+        buyer: info.sender.clone(),
+        price,
+        created_at: env.block.time,
+    };
+    OFFERS.save(deps.storage, (&username, offer_id), &offer)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "make_offer")
+        .add_attribute("username", username)
+        .add_attribute("offer_id", offer_id.to_string())
+        .add_attribute("buyer", info.sender)
+        .add_attribute("price", offer.price.to_string()))
+}
+
+fn execute_withdraw_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+    offer_id: u64,
+) -> Result<Response, ContractError> {
+    let offer = OFFERS
+        .may_load(deps.storage, (&username, offer_id))?
+        .ok_or(ContractError::OfferNotFound { offer_id })?;
+    if offer.buyer != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    OFFERS.remove(deps.storage, (&username, offer_id));
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![offer.price],
+        })
+        .add_attribute("method", "withdraw_offer")
+        .add_attribute("username", username)
+        .add_attribute("offer_id", offer_id.to_string()))
+}
+
+fn execute_accept_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_id: u64,
+    keep_profile_data: bool,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    // NFT-backed usernames are sold by sending the token, unless it is already escrowed
+    let nft_escrowed = match LISTINGS.may_load(deps.storage, &username)? {
+        Some(listing) => listing.nft_escrowed,
+        None => false,
+    };
+    if !nft_escrowed && PROFILE_NFTS.has(deps.storage, &username) {
+        return Err(ContractError::ProfileNftRequired { username });
+    }
+    
+    accept_offer(deps, env, info.sender, username, offer_id, keep_profile_data, nft_escrowed)
+}
+
+fn execute_claim_profile_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    if PROFILE_NFTS.has(deps.storage, &username) {
+        return Err(ContractError::ProfileNftExists { username });
+    }
+    // A listing made without the token would let the seller be paid while keeping the NFT
+    if LISTINGS.has(deps.storage, &username) {
+        return Err(ContractError::UsernameListed { username });
+    }
+    
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    let mint = profile_nft_mint(deps.storage, &profile)?.ok_or(ContractError::ProfileNftsDisabled {})?;
+    
+    Ok(Response::new()
+        .add_message(mint)
+        .add_attribute("method", "claim_profile_nft")
+        .add_attribute("username", username))
+}
+
+fn execute_sync_profile_owner(
+    deps: DepsMut,
+    env: Env,
+    username: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let nft_contract = config
+        .profile_nft_contract
+        .ok_or(ContractError::ProfileNftsDisabled {})?;
+    if !PROFILE_NFTS.has(deps.storage, &username) {
+        return Err(ContractError::UsernameNotFound { username });
+    }
+    
+    let owner: Cw721OwnerOfResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_contract.to_string(),
+        msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
+            token_id: profile_token_id(deps.storage, &username)?,
+            include_expired: None,
+        })?,
+    }))?;
+    let owner = addr_validate(deps.api, &owner.owner)?;
+    
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    let changed = profile.wallet_address != owner && owner != env.contract.address;
+    let mut settlement_msgs = Vec::new();
+    if changed {
+        (_, settlement_msgs) = move_profile(deps, &env, &username, &owner)?;
+    }
+    
+    Ok(Response::new()
+        .add_messages(settlement_msgs)
+        .add_attribute("method", "sync_profile_owner")
+        .add_attribute("username", username)
+        .add_attribute("owner", owner)
+        .add_attribute("changed", changed.to_string()))
+}
+
+fn execute_set_receipt_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    threshold: Option<Coin>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    match &threshold {
+        Some(threshold) if threshold.amount.is_zero() => {
+            return Err(ContractError::InvalidAmount {
+                amount: threshold.to_string(),
+            });
+        }
+        Some(threshold) => RECEIPT_THRESHOLDS.save(deps.storage, &username, threshold)?,
+        None => RECEIPT_THRESHOLDS.remove(deps.storage, &username),
+    }
+    
+    Ok(Response::new()
+        .add_attribute("method", "set_receipt_threshold")
+        .add_attribute("username", username)
+        .add_attribute(
+            "threshold",
+            threshold.map(|coin| coin.to_string()).unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
+fn execute_set_encryption_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    encryption_key: Option<EncryptionKey>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    if let Some(key) = &encryption_key {
+        validate_encryption_scheme(&key.scheme)?;
+        if key.public_key.is_empty() || key.public_key.len() > MAX_ENCRYPTION_KEY_BYTES {
+            return Err(ContractError::InvalidEncryption {
+                reason: format!("public key must be 1 to {} bytes", MAX_ENCRYPTION_KEY_BYTES),
+            });
+        }
+    }
+    
+    let mut profile = USER_PROFILES.load(deps.storage, &username)?;
+    let scheme = encryption_key.as_ref().map(|key| key.scheme.clone());
+    profile.encryption_key = encryption_key;
+    profile.updated_at = env.block.time;
+    USER_PROFILES.save(deps.storage, &username, &profile)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "set_encryption_key")
+        .add_attribute("username", username)
+        .add_attribute("scheme", scheme.unwrap_or_else(|| "none".to_string())))
+}
+
+fn execute_batch_tip(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tips: Vec<BatchTipEntry>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    if tips.is_empty() || tips.len() > MAX_BATCH_TIPS {
+        return Err(ContractError::InvalidBatch {
+            reason: format!("a batch must contain between 1 and {} tips", MAX_BATCH_TIPS),
+        });
+    }
+    
+    let from_username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    ensure_not_suspended(deps.as_ref(), &from_username)?;
+    
+    // Validate every entry before anything is written
+    let mut expected_funds: Vec<Coin> = Vec::new();
+    let mut validated = Vec::with_capacity(tips.len());
+    for entry in tips {
+        if entry.to_username == from_username {
+            return Err(ContractError::SelfTipping {});
+        }
+        let recipient = USER_PROFILES
+            .may_load(deps.storage, &entry.to_username)?
+            .ok_or_else(|| ContractError::UsernameNotFound {
+                username: entry.to_username.clone(),
+            })?;
+        ensure_not_suspended(deps.as_ref(), &entry.to_username)?;
+        
+        let mut message = entry.message;
+        if is_blocked(deps.as_ref(), &entry.to_username, Some(&from_username), &info.sender)? {
+            match BLOCK_POLICIES.may_load(deps.storage, &entry.to_username)?.unwrap_or_default() {
+                BlockPolicy::RejectTip => {
+                    return Err(ContractError::SenderBlocked {
+                        username: entry.to_username,
+                    });
+                }
+                BlockPolicy::DropMessage => message = None,
+            }
+        }
+        
+        let (value, denom) = parse_amount(&entry.amount)
+            .filter(|(value, _)| !value.is_zero())
+            .ok_or_else(|| ContractError::InvalidAmount {
+                amount: entry.amount.clone(),
+            })?;
+        let funds = Coin::new(value.u128(), denom);
+        add_coin(&mut expected_funds, funds.clone());
+        
+        validated.push((recipient, entry.amount, message, funds));
+    }
+    
+    // Attached funds must cover the batch exactly
+    let mut attached: Vec<Coin> = Vec::new();
+    for coin in &info.funds {
+        add_coin(&mut attached, coin.clone());
+    }
+    attached.sort_by(|a, b| a.denom.cmp(&b.denom));
+    expected_funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+    if attached != expected_funds {
+        return Err(ContractError::InvalidFunds {
+            reason: format!(
+                "attached funds must equal {}",
+                expected_funds
+                    .iter()
+                    .map(|coin| coin.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        });
+    }
+    
+    let mut response = Response::new()
+        .add_attribute("method", "batch_tip")
+        .add_attribute("from", from_username.clone())
+        .add_attribute("count", validated.len().to_string());
+    
+    for (recipient, amount, message, funds) in validated {
+        let (net, referral_msg) = split_tip_funds(deps.branch(), env.block.time, &recipient, funds)?;
+        let tip = TipRecord {
+            from_username: from_username.clone(),
+            to_username: recipient.username.clone(),
+            amount,
+            message,
+            timestamp: env.block.time,
+            moderation: None,
+            goal_id: None,
+            status: TipStatus::Completed,
+            reply: None,
+            encrypted_message: None,
+            visibility: TipVisibility::default(),
+            paid_by: Some(info.sender.clone()),
+            delivered: Some(net.clone()),
+        };
+        let (tip_key, _) = store_tip(deps.storage, tip)?;
+        response = response
+            .add_attribute("tip_id", tip_key)
+            .add_message(BankMsg::Send {
+                to_address: profile_owner(deps.as_ref(), &recipient)?.to_string(),
+                amount: vec![net],
+            })
+            .add_messages(referral_msg);
+    }
+    
+    Ok(response)
+}
+
+fn hold_tip_for_unregistered_name(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_username: String,
+    to_username: String,
+    amount: String,
+    message: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_username(&to_username)?;
+    let funds = tip_funds(&info, &amount)?.ok_or_else(|| ContractError::InvalidFunds {
+        reason: "tips to unregistered usernames must attach the tip amount".to_string(),
+    })?;
+    
+    let waiting = UNREGISTERED_NAME_TIPS
+        .prefix(&to_username)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, held)| held.sender))
+        .collect::<StdResult<Vec<_>>>()?;
+    if waiting.len() >= MAX_UNREGISTERED_NAME_TIPS {
+        return Err(ContractError::TooManyUnregisteredTips { username: to_username });
+    }
+    let from_sender = waiting.iter().filter(|sender| **sender == info.sender).count();
+    if from_sender >= MAX_UNREGISTERED_NAME_TIPS_PER_SENDER {
+        return Err(ContractError::TooManyUnregisteredTipsFromSender { username: to_username });
+    }
+    
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let tip_id = UNREGISTERED_NAME_TIP_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    UNREGISTERED_NAME_TIP_SEQ.save(deps.storage, &tip_id)?;
+    
+    let held = UnregisteredNameTip {
+        id: tip_id,
+        sender: info.sender,
+        from_username: from_username.clone(),
+        to_username: to_username.clone(),
+        funds,
+        message,
+        created_at: env.block.time,
+        expires_at: env.block.time.plus_seconds(config.unregistered_tip_expiry_seconds),
+    };
+    UNREGISTERED_NAME_TIPS.save(deps.storage, (&to_username, tip_id), &held)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "record_tip")
+        .add_attribute("from", from_username)
+        .add_attribute("to", to_username)
+        .add_attribute("held_tip_id", tip_id.to_string())
+        .add_attribute("expires_at", held.expires_at.to_string()))
+}
+
+fn execute_reclaim_unregistered_tip(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+    tip_id: u64,
+) -> Result<Response, ContractError> {
+    let held = UNREGISTERED_NAME_TIPS
+        .may_load(deps.storage, (&username, tip_id))?
+        .ok_or(ContractError::UnregisteredTipNotFound { tip_id })?;
+    
+    if held.sender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time < held.expires_at {
+        return Err(ContractError::TipNotReclaimable {
+            tip_id: tip_id.to_string(),
+            reclaimable_at: held.expires_at.to_string(),
+        });
+    }
+    
+    UNREGISTERED_NAME_TIPS.remove(deps.storage, (&username, tip_id));
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![held.funds],
+        })
+        .add_attribute("method", "reclaim_unregistered_tip")
+        .add_attribute("username", username)
+        .add_attribute("held_tip_id", tip_id.to_string()))
+}
+
+fn execute_claim_tips(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tip_ids: Option<Vec<String>>,
+    limit: Option<u32>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    ensure_not_suspended(deps.as_ref(), &username)?;
+    
+    let tip_ids = match tip_ids {
+        Some(tip_ids) => tip_ids,
+        None => PENDING_TIPS
+            .prefix(&username)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(limit.unwrap_or(30) as usize)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+    
+    let recipient = USER_PROFILES.load(deps.storage, &username)?;
+    let mut payouts: Vec<Coin> = Vec::new();
+    let mut referral_msgs = Vec::new();
+    let mut receipts = Vec::new();
+    for tip_id in &tip_ids {
+        if !PENDING_TIPS.has(deps.storage, (&username, tip_id)) {
+            return Err(ContractError::TipNotPending { tip_id: tip_id.clone() });
+        }
+        
+        let escrowed = ESCROWED_TIPS.load(deps.storage, tip_id)?;
+        let (net, referral_msg) =
+            split_tip_funds(deps.branch(), env.block.time, &recipient, escrowed.funds.clone())?;
+        let mut tip = settle_escrowed_tip(deps.storage, tip_id, TipStatus::Claimed)?;
+        tip.delivered = Some(net.clone());
+        TIP_RECORDS.save(deps.storage, tip_id, &tip)?;
+        add_coin(&mut payouts, net);
+        referral_msgs.extend(referral_msg);
+        
+        // The receipt is earned once the escrowed funds reach the recipient
+        receipts.extend(receipt_submsg(
+            deps.branch(),
+            &env,
+            &escrowed.sender,
+            tip_id,
+            &tip,
+            &escrowed.funds,
+        )?);
+    }
+    
+    if tip_ids.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: payouts,
+        })
+        .add_messages(referral_msgs)
+        .add_submessages(receipts)
+        .add_attribute("method", "claim_tips")
+        .add_attribute("username", username)
+        .add_attribute("claimed", tip_ids.len().to_string()))
+}
+
+fn execute_reclaim_tip(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tip_id: String,
+) -> Result<Response, ContractError> {
+    let escrowed = ESCROWED_TIPS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or_else(|| ContractError::TipNotPending { tip_id: tip_id.clone() })?;
+    
+    if escrowed.sender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time < escrowed.reclaimable_at {
+        return Err(ContractError::TipNotReclaimable {
+            tip_id,
+            reclaimable_at: escrowed.reclaimable_at.to_string(),
+        });
+    }
+    
+    let tip = settle_escrowed_tip(deps.storage, &tip_id, TipStatus::Reclaimed)?;
+    
+    // Reclaimed funds no longer count towards the recipient's goal
+    if let Some(goal_id) = tip.goal_id {
+        release_goal_earmark(deps.storage, &tip.to_username, goal_id, escrowed.funds.amount)?;
+    }
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![escrowed.funds],
+        })
+        .add_attribute("method", "reclaim_tip")
+        .add_attribute("tip_id", tip_id))
+}
+
+fn execute_refund_tip(
+    deps: DepsMut,
+    info: MessageInfo,
+    tip_id: String,
+) -> Result<Response, ContractError> {
+    let tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    let recipient = USER_PROFILES.load(deps.storage, &tip.to_username)?;
+    if profile_owner(deps.as_ref(), &recipient)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Tips sent before the name was last registered belong to its previous owner
+    if tip.timestamp < recipient.created_at {
+        return Err(ContractError::TipNotRefundable { tip_id });
+    }
+    
+    // Escrowed funds go straight back to the wallet that attached them; delivered
+    // tips are paid back to the wallet that paid them, out of the funds the recipient attaches.
+    // The recipient only returns what reached them, the platform fee is not reversed.
+    // Record-only tips never moved funds, so there is nothing to return.
+    let (tip, refund_to, funds, earmarked) = match tip.status {
+        TipStatus::Pending => {
+            let escrowed = ESCROWED_TIPS.load(deps.storage, &tip_id)?;
+            let tip = settle_escrowed_tip(deps.storage, &tip_id, TipStatus::Refunded)?;
+            let earmarked = escrowed.funds.amount;
+            (tip, escrowed.sender, escrowed.funds, earmarked)
+        }
+        TipStatus::Completed | TipStatus::Claimed => {
+            let (payer, funds) = match (&tip.paid_by, &tip.delivered) {
+                (Some(payer), Some(delivered)) if !delivered.amount.is_zero() => {
+                    (payer.clone(), delivered.clone())
+                }
+                _ => return Err(ContractError::TipNotRefundable { tip_id }),
+            };
+            ensure_exact_funds(&info, &funds)?;
+            let earmarked = parse_amount(&tip.amount).map(|(value, _)| value).unwrap_or_default();
+            
+            let mut tip = tip;
+            tip.status = TipStatus::Refunded;
+            TIP_RECORDS.save(deps.storage, &tip_id, &tip)?;
+            (tip, payer, funds, earmarked)
+        }
+        TipStatus::Reclaimed | TipStatus::Refunded => {
+            return Err(ContractError::TipNotRefundable { tip_id });
+        }
+    };
+    
+    REFUND_REQUESTS.remove(deps.storage, (&tip.to_username, &tip_id));
+    
+    // Refunded funds no longer count towards the recipient's goal
+    if let Some(goal_id) = tip.goal_id {
+        release_goal_earmark(deps.storage, &tip.to_username, goal_id, earmarked)?;
+    }
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: refund_to.to_string(),
+            amount: vec![funds],
+        })
+        .add_attribute("method", "refund_tip")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("refunded_to", refund_to))
+}
+
+fn execute_reply_to_tip(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tip_id: String,
+    message: String,
+) -> Result<Response, ContractError> {
+    let mut tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    let recipient = USER_PROFILES.load(deps.storage, &tip.to_username)?;
+    if profile_owner(deps.as_ref(), &recipient)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    // Tips sent before the name was last registered belong to its previous owner
+    if tip.timestamp < recipient.created_at {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_not_suspended(deps.as_ref(), &tip.to_username)?;
+    
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        return Err(ContractError::MissingField {
+            field: "message".to_string(),
+        });
+    }
+    
+    // One reply per tip; posting again edits it unless a moderator redacted it
+    let edited = tip.reply.is_some();
+    let reply = match tip.reply.take() {
+        Some(reply) if reply.moderation.is_some() => {
+            return Err(ContractError::ReplyRedacted { tip_id });
+        }
+        Some(reply) => TipReply {
+            message: Some(message),
+            edited_at: Some(env.block.time),
+            ..reply
+        },
+        None => TipReply {
+            message: Some(message),
+            replied_at: env.block.time,
+            edited_at: None,
+            moderation: None,
+        },
+    };
+    tip.reply = Some(reply);
+    TIP_RECORDS.save(deps.storage, &tip_id, &tip)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "reply_to_tip")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("edited", edited.to_string()))
+}
+
+fn execute_request_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tip_id: String,
+    reason: Option<String>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let from_username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    if tip.from_username != from_username {
+        return Err(ContractError::Unauthorized {});
+    }
+    if tip.paid_by.is_none() || matches!(tip.status, TipStatus::Reclaimed | TipStatus::Refunded) {
+        return Err(ContractError::TipNotRefundable { tip_id });
+    }
+    
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if env.block.time > tip.timestamp.plus_seconds(config.refund_request_window_seconds) {
+        return Err(ContractError::RefundWindowClosed { tip_id });
+    }
+    if REFUND_REQUESTS.has(deps.storage, (&tip.to_username, &tip_id)) {
+        return Err(ContractError::RefundAlreadyRequested { tip_id });
+    }
+    
+    let request = RefundRequest {
+        tip_id: tip_id.clone(),
+        from_username,
+        reason,
+        requested_at: env.block.time,
+    };
+    REFUND_REQUESTS.save(deps.storage, (&tip.to_username, &tip_id), &request)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "request_refund")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("to", tip.to_username))
+}
+
+fn execute_create_goal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    description: String,
+    target: Coin,
+    deadline: Timestamp,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    ensure_not_suspended(deps.as_ref(), &username)?;
+    
+    if description.trim().is_empty() {
+        return Err(ContractError::MissingField {
+            field: "description".to_string(),
+        });
+    }
+    if target.amount.is_zero() {
+        return Err(ContractError::MissingField {
+            field: "target".to_string(),
+        });
+    }
+    if deadline <= env.block.time {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    
+    let goal_id = GOAL_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    GOAL_SEQ.save(deps.storage, &goal_id)?;
+    
+    let goal = FundraisingGoal {
+        id: goal_id,
+        username: username.clone(),
+        description,
+        target: target.amount,
+        denom: target.denom,
+        deadline,
+        raised: Uint128::zero(),
+        created_at: env.block.time,
+        completed_at: None,
+        closed_at: None,
+    };
+    GOALS.save(deps.storage, (&username, goal_id), &goal)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "create_goal")
+        .add_attribute("username", username)
+        .add_attribute("goal_id", goal_id.to_string())
+        .add_attribute("target", format!("{}{}", goal.target, goal.denom))
+        .add_attribute("deadline", deadline.to_string()))
+}
+
+fn execute_close_goal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    goal_id: u64,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    let mut goal = GOALS
+        .may_load(deps.storage, (&username, goal_id))?
+        .ok_or(ContractError::GoalNotFound { goal_id })?;
+    if goal.status(env.block.time) != GoalStatus::Active {
+        return Err(ContractError::GoalNotActive { goal_id });
+    }
+    
+    goal.closed_at = Some(env.block.time);
+    GOALS.save(deps.storage, (&username, goal_id), &goal)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "close_goal")
+        .add_attribute("username", username)
+        .add_attribute("goal_id", goal_id.to_string())
+        .add_attribute("raised", format!("{}{}", goal.raised, goal.denom)))
+}
+
+fn execute_create_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    description: String,
+    target: Coin,
+    deadline: Timestamp,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let creator = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    ensure_not_suspended(deps.as_ref(), &creator)?;
+    
+    if description.trim().is_empty() {
+        return Err(ContractError::MissingField {
+            field: "description".to_string(),
+        });
+    }
+    if target.amount.is_zero() {
+        return Err(ContractError::MissingField {
+            field: "target".to_string(),
+        });
+    }
+    if deadline <= env.block.time {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    
+    let campaign_id = CAMPAIGN_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    CAMPAIGN_SEQ.save(deps.storage, &campaign_id)?;
+    
+    let campaign = Campaign {
+        id: campaign_id,
+        creator: creator.clone(),
+        description,
+        target: target.amount,
+        denom: target.denom,
+        deadline,
+        pledged: Uint128::zero(),
+        created_at: env.block.time,
+        funds_released: false,
+        cancelled_at: None,
+    };
+    CAMPAIGNS.save(deps.storage, campaign_id, &campaign)?;
+    CREATOR_CAMPAIGNS.save(deps.storage, (&creator, campaign_id), &true)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "create_campaign")
+        .add_attribute("creator", creator)
+        .add_attribute("campaign_id", campaign_id.to_string())
+        .add_attribute("target", format!("{}{}", campaign.target, campaign.denom))
+        .add_attribute("deadline", deadline.to_string()))
+}
+
+fn execute_pledge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+) -> Result<Response, ContractError> {
+    let mut campaign = CAMPAIGNS
+        .may_load(deps.storage, campaign_id)?
+        .ok_or(ContractError::CampaignNotFound { campaign_id })?;
+    
+    if campaign.status(env.block.time) != CampaignStatus::Open {
+        return Err(ContractError::CampaignWrongStatus {
+            campaign_id,
+            expected: "open".to_string(),
+        });
+    }
+    ensure_not_suspended(deps.as_ref(), &campaign.creator)?;
+    
+    if holds_profile(deps.as_ref(), &info.sender, &campaign.creator)? {
+        return Err(ContractError::SelfTipping {});
+    }
+    if is_wallet_blocked(deps.as_ref(), &campaign.creator, &info.sender)? {
+        return Err(ContractError::SenderBlocked { username: campaign.creator });
+    }
+    
+    // Only the campaign's denom is accepted
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == campaign.denom && !coin.amount.is_zero() => coin.amount,
+        _ => {
+            return Err(ContractError::InvalidFunds {
+                reason: format!("attach a non-zero amount of {}", campaign.denom),
+            })
+        }
+    };
+    
+    campaign.pledged += amount;
+    CAMPAIGNS.save(deps.storage, campaign_id, &campaign)?;
+    CAMPAIGN_PLEDGES.update(deps.storage, (campaign_id, &info.sender), |pledged| -> StdResult<_> {
+        Ok(pledged.unwrap_or_default() + amount)
+    })?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "pledge")
+        .add_attribute("campaign_id", campaign_id.to_string())
+        .add_attribute("pledger", info.sender)
+        .add_attribute("amount", format!("{}{}", amount, campaign.denom)))
+}
+
+fn execute_claim_campaign_funds(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+) -> Result<Response, ContractError> {
+    let mut campaign = CAMPAIGNS
+        .may_load(deps.storage, campaign_id)?
+        .ok_or(ContractError::CampaignNotFound { campaign_id })?;
+    
+    // Funds go to the current owner of the creator profile
+    let creator = USER_PROFILES.load(deps.storage, &campaign.creator)?;
+    if profile_owner(deps.as_ref(), &creator)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if campaign.status(env.block.time) != CampaignStatus::Succeeded {
+        return Err(ContractError::CampaignWrongStatus {
+            campaign_id,
+            expected: "succeeded".to_string(),
+        });
+    }
+    if campaign.funds_released {
+        return Err(ContractError::NothingToClaim {});
+    }
+    
+    campaign.funds_released = true;
+    CAMPAIGNS.save(deps.storage, campaign_id, &campaign)?;
+    let (net, referral_msg) = split_tip_funds(
+        deps.branch(),
+        env.block.time,
+        &creator,
+        Coin::new(campaign.pledged.u128(), &campaign.denom),
+    )?;
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![net],
+        })
+        .add_messages(referral_msg)
+        .add_attribute("method", "claim_campaign_funds")
+        .add_attribute("campaign_id", campaign_id.to_string())
+        .add_attribute("amount", format!("{}{}", campaign.pledged, campaign.denom)))
+}
+
+fn execute_claim_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+) -> Result<Response, ContractError> {
+    let campaign = CAMPAIGNS
+        .may_load(deps.storage, campaign_id)?
+        .ok_or(ContractError::CampaignNotFound { campaign_id })?;
+    
+    if campaign.status(env.block.time) != CampaignStatus::Failed {
+        return Err(ContractError::CampaignWrongStatus {
+            campaign_id,
+            expected: "failed".to_string(),
+        });
+    }
+    
+    // Each pledger claims their own contribution exactly once
+    let amount = CAMPAIGN_PLEDGES
+        .may_load(deps.storage, (campaign_id, &info.sender))?
+        .ok_or(ContractError::NothingToClaim {})?;
+    CAMPAIGN_PLEDGES.remove(deps.storage, (campaign_id, &info.sender));
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount.u128(), &campaign.denom)],
+        })
+        .add_attribute("method", "claim_refund")
+        .add_attribute("campaign_id", campaign_id.to_string())
+        .add_attribute("pledger", info.sender)
+        .add_attribute("amount", format!("{}{}", amount, campaign.denom)))
+}
+
+fn execute_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to_username: String,
+    duration_seconds: u64,
+    message: Option<String>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let from_username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    if from_username == to_username {
+        return Err(ContractError::SelfTipping {});
+    }
+    if !USER_PROFILES.has(deps.storage, &to_username) {
+        return Err(ContractError::UsernameNotFound { username: to_username });
+    }
+    ensure_not_suspended(deps.as_ref(), &from_username)?;
+    ensure_not_suspended(deps.as_ref(), &to_username)?;
+    if is_blocked(deps.as_ref(), &to_username, Some(&from_username), &info.sender)? {
+        return Err(ContractError::SenderBlocked { username: to_username });
+    }
+    
+    if duration_seconds == 0 {
+        return Err(ContractError::MissingField {
+            field: "duration_seconds".to_string(),
+        });
+    }
+    
+    // The streamed amount is whatever single coin was attached
+    let funds = match info.funds.as_slice() {
+        [coin] if !coin.amount.is_zero() => coin.clone(),
+        _ => {
+            return Err(ContractError::InvalidFunds {
+                reason: "attach exactly one non-zero coin".to_string(),
+            })
+        }
+    };
+    
+    let stream_id = TIP_STREAM_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    TIP_STREAM_SEQ.save(deps.storage, &stream_id)?;
+    
+    let stream = TipStream {
+        id: stream_id,
+        sender: info.sender.clone(),
+        from_username: from_username.clone(),
+        to_username: to_username.clone(),
+        denom: funds.denom,
+        total: funds.amount,
+        withdrawn: Uint128::zero(),
+        start: env.block.time,
+        end: env.block.time.plus_seconds(duration_seconds),
+        message,
+        cancelled_at: None,
+    };
+    TIP_STREAMS.save(deps.storage, stream_id, &stream)?;
+    STREAMS_SENT.save(deps.storage, (&from_username, stream_id), &true)?;
+    STREAMS_RECEIVED.save(deps.storage, (&to_username, stream_id), &true)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "create_stream")
+        .add_attribute("stream_id", stream_id.to_string())
+        .add_attribute("from", from_username)
+        .add_attribute("to", to_username)
+        .add_attribute("total", format!("{}{}", stream.total, stream.denom))
+        .add_attribute("end", stream.end.to_string()))
+}
+
+fn execute_withdraw_stream(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = TIP_STREAMS
+        .may_load(deps.storage, stream_id)?
+        .ok_or(ContractError::StreamNotFound { stream_id })?;
+    
+    // Only the current owner of the recipient profile can withdraw
+    let recipient = USER_PROFILES.load(deps.storage, &stream.to_username)?;
+    if profile_owner(deps.as_ref(), &recipient)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_not_suspended(deps.as_ref(), &stream.to_username)?;
+    
+    let amount = stream.vested(env.block.time) - stream.withdrawn;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    
+    // The stream's message travels with the first withdrawal only
+    let message = if stream.withdrawn.is_zero() {
+        stream.message.clone()
+    } else {
+        None
+    };
+    stream.withdrawn += amount;
+    TIP_STREAMS.save(deps.storage, stream_id, &stream)?;
+    let (net, referral_msg) = split_tip_funds(
+        deps.branch(),
+        env.block.time,
+        &recipient,
+        Coin::new(amount.u128(), &stream.denom),
+    )?;
+    
+    // Each withdrawal is a regular tip so history and stats stay coherent
+    let tip = TipRecord {
+        from_username: stream.from_username.clone(),
+        to_username: stream.to_username.clone(),
+        amount: format!("{}{}", amount, stream.denom),
+        message,
+        timestamp: env.block.time,
+        moderation: None,
+        goal_id: None,
+        status: TipStatus::Completed,
+        reply: None,
+        encrypted_message: None,
+        visibility: TipVisibility::default(),
+        paid_by: Some(stream.sender.clone()),
+        delivered: Some(net.clone()),
+    };
+    let (tip_key, _) = store_tip(deps.storage, tip)?;
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![net],
+        })
+        .add_messages(referral_msg)
+        .add_attribute("method", "withdraw_stream")
+        .add_attribute("stream_id", stream_id.to_string())
+        .add_attribute("amount", format!("{}{}", amount, stream.denom))
+        .add_attribute("tip_id", tip_key))
+}
+
+fn execute_cancel_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = TIP_STREAMS
+        .may_load(deps.storage, stream_id)?
+        .ok_or(ContractError::StreamNotFound { stream_id })?;
+    
+    if stream.sender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if stream.cancelled_at.is_some() {
+        return Err(ContractError::StreamCancelled { stream_id });
+    }
+    
+    // Freeze vesting now; the vested part stays withdrawable by the recipient
+    let vested = stream.vested(env.block.time);
+    let refund = stream.total - vested;
+    stream.total = vested;
+    stream.end = env.block.time;
+    stream.cancelled_at = Some(env.block.time);
+    TIP_STREAMS.save(deps.storage, stream_id, &stream)?;
+    
+    let mut response = Response::new()
+        .add_attribute("method", "cancel_stream")
+        .add_attribute("stream_id", stream_id.to_string())
+        .add_attribute("refund", format!("{}{}", refund, stream.denom));
+    
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(refund.u128(), &stream.denom)],
+        });
+    }
+    
+    Ok(response)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_scheduled_tip(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to_username: String,
+    amount: Coin,
+    interval_seconds: u64,
+    start_at: Option<Timestamp>,
+    message: Option<String>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let from_username = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    if from_username == to_username {
+        return Err(ContractError::SelfTipping {});
+    }
+    if !USER_PROFILES.has(deps.storage, &to_username) {
+        return Err(ContractError::UsernameNotFound { username: to_username });
+    }
+    ensure_not_suspended(deps.as_ref(), &from_username)?;
+    ensure_not_suspended(deps.as_ref(), &to_username)?;
+    if is_blocked(deps.as_ref(), &to_username, Some(&from_username), &info.sender)? {
+        return Err(ContractError::SenderBlocked { username: to_username });
+    }
+    
+    if amount.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {
+            amount: amount.to_string(),
+        });
+    }
+    if interval_seconds < MIN_SCHEDULE_INTERVAL_SECONDS {
+        return Err(ContractError::CustomError {
+            message: format!("Interval must be at least {} seconds", MIN_SCHEDULE_INTERVAL_SECONDS),
+        });
+    }
+    let next_run_at = start_at.unwrap_or(env.block.time).max(env.block.time);
+    
+    // The budget must be in the tip denom and cover at least one execution
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let budget = match info.funds.as_slice() {
+        [coin] if coin.denom == amount.denom => coin.amount,
+        _ => {
+            return Err(ContractError::InvalidFunds {
+                reason: format!("attach the budget in {}", amount.denom),
+            })
+        }
+    };
+    if budget < scheduled_tip_cost(&amount, config.keeper_reward_bps) {
+        return Err(ContractError::InvalidFunds {
+            reason: "budget does not cover a single tip and keeper reward".to_string(),
+        });
+    }
+    
+    let schedule_id = SCHEDULED_TIP_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SCHEDULED_TIP_SEQ.save(deps.storage, &schedule_id)?;
+    
+    let schedule = ScheduledTip {
+        id: schedule_id,
+        sender: info.sender.clone(),
+        from_username: from_username.clone(),
+        to_username: to_username.clone(),
+        amount,
+        interval_seconds,
+        next_run_at,
+        budget,
+        executions: 0,
+        message,
+        ended_at: None,
+    };
+    SCHEDULED_TIPS.save(deps.storage, schedule_id, &schedule)?;
+    SCHEDULED_TIPS_SENT.save(deps.storage, (&from_username, schedule_id), &true)?;
+    SCHEDULED_TIPS_RECEIVED.save(deps.storage, (&to_username, schedule_id), &true)?;
+    DUE_SCHEDULED_TIPS.save(deps.storage, (next_run_at.seconds(), schedule_id), &true)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "create_scheduled_tip")
+        .add_attribute("schedule_id", schedule_id.to_string())
+        .add_attribute("from", from_username)
+        .add_attribute("to", to_username)
+        .add_attribute("amount", schedule.amount.to_string())
+        .add_attribute("next_run_at", next_run_at.to_string()))
+}
+
+fn execute_cancel_scheduled_tip(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    schedule_id: u64,
+) -> Result<Response, ContractError> {
+    let mut schedule = SCHEDULED_TIPS
+        .may_load(deps.storage, schedule_id)?
+        .ok_or(ContractError::ScheduledTipNotFound { schedule_id })?;
+    
+    if schedule.sender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if schedule.ended_at.is_some() {
+        return Err(ContractError::ScheduledTipEnded { schedule_id });
+    }
+    
+    let refund = Coin::new(schedule.budget.u128(), &schedule.amount.denom);
+    DUE_SCHEDULED_TIPS.remove(deps.storage, (schedule.next_run_at.seconds(), schedule_id));
+    schedule.budget = Uint128::zero();
+    schedule.ended_at = Some(env.block.time);
+    SCHEDULED_TIPS.save(deps.storage, schedule_id, &schedule)?;
+    
+    let mut response = Response::new()
+        .add_attribute("method", "cancel_scheduled_tip")
+        .add_attribute("schedule_id", schedule_id.to_string())
+        .add_attribute("refund", refund.to_string());
+    
+    if !refund.amount.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![refund],
+        });
+    }
+    
+    Ok(response)
+}
+
+fn execute_due_tips(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(10).min(MAX_DUE_TIPS_PER_CALL) as usize;
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    
+    let due = DUE_SCHEDULED_TIPS
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::inclusive((env.block.time.seconds(), u64::MAX))),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    let mut response = Response::new().add_attribute("method", "execute_due_tips");
+    let mut keeper_rewards: Vec<Coin> = Vec::new();
+    let mut executed = 0u32;
+    let mut skipped = 0u32;
+    
+    for (run_at, schedule_id) in due {
+        DUE_SCHEDULED_TIPS.remove(deps.storage, (run_at, schedule_id));
+        let mut schedule = SCHEDULED_TIPS.load(deps.storage, schedule_id)?;
+        schedule.next_run_at = schedule.next_run_at.plus_seconds(schedule.interval_seconds);
+        
+        // Recipients that are suspended or have blocked the sender are skipped this period
+        let deliverable = !SUSPENDED_PROFILES.has(deps.storage, &schedule.to_username)
+            && !SUSPENDED_PROFILES.has(deps.storage, &schedule.from_username)
+            && !is_blocked(
+                deps.as_ref(),
+                &schedule.to_username,
+                Some(&schedule.from_username),
+                &schedule.sender,
+            )?;
+        
+        let cost = scheduled_tip_cost(&schedule.amount, config.keeper_reward_bps);
+        if deliverable && schedule.budget >= cost {
+            schedule.budget -= cost;
+            schedule.executions += 1;
+            
+            let recipient = USER_PROFILES.load(deps.storage, &schedule.to_username)?;
+            let (net, referral_msg) =
+                split_tip_funds(deps.branch(), env.block.time, &recipient, schedule.amount.clone())?;
+            let tip = TipRecord {
+                from_username: schedule.from_username.clone(),
+                to_username: schedule.to_username.clone(),
+                amount: format!("{}{}", schedule.amount.amount, schedule.amount.denom),
+                message: schedule.message.clone(),
+                timestamp: env.block.time,
+                moderation: None,
+                goal_id: None,
+                status: TipStatus::Completed,
+                reply: None,
+                encrypted_message: None,
+                visibility: TipVisibility::default(),
+                paid_by: Some(schedule.sender.clone()),
+                delivered: Some(net.clone()),
+            };
+            let (tip_key, _) = store_tip(deps.storage, tip)?;
+            
+            response = response
+                .add_message(BankMsg::Send {
+                    to_address: profile_owner(deps.as_ref(), &recipient)?.to_string(),
+                    amount: vec![net],
+                })
+                .add_messages(referral_msg)
+                .add_attribute("tip_id", tip_key);
+            add_coin(
+                &mut keeper_rewards,
+                Coin::new((cost - schedule.amount.amount).u128(), &schedule.amount.denom),
+            );
+            executed += 1;
+        } else {
+            skipped += 1;
+        }
+        
+        // Schedules that can no longer pay for an execution end and return the remainder
+        if schedule.budget < cost {
+            schedule.ended_at = Some(env.block.time);
+            if !schedule.budget.is_zero() {
+                response = response.add_message(BankMsg::Send {
+                    to_address: schedule.sender.to_string(),
+                    amount: vec![Coin::new(schedule.budget.u128(), &schedule.amount.denom)],
+                });
+                schedule.budget = Uint128::zero();
+            }
+        } else {
+            DUE_SCHEDULED_TIPS.save(
+                deps.storage,
+                (schedule.next_run_at.seconds(), schedule_id),
+                &true,
+            )?;
+        }
+        SCHEDULED_TIPS.save(deps.storage, schedule_id, &schedule)?;
+    }
+    
+    keeper_rewards.retain(|coin| !coin.amount.is_zero());
+    if !keeper_rewards.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: keeper_rewards,
+        });
+    }
+    
+    Ok(response
+        .add_attribute("executed", executed.to_string())
+        .add_attribute("skipped", skipped.to_string())
+        .add_attribute("keeper", info.sender))
+}
+
+fn execute_block(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target: BlockTarget,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let target = normalize_block_target(deps.as_ref(), target)?;
+    
+    if target == BlockTarget::Username(owner.clone()) {
+        return Err(ContractError::CustomError {
+            message: "Cannot block yourself".to_string(),
+        });
+    }
+    
+    let entry = BlockedEntry {
+        target: target.clone(),
+        blocked_at: env.block.time,
+    };
+    BLOCKLIST.save(deps.storage, (&owner, &target.key()), &entry)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "block")
+        .add_attribute("username", owner)
+        .add_attribute("target", target.key()))
+}
+
+fn execute_unblock(
+    deps: DepsMut,
+    info: MessageInfo,
+    target: BlockTarget,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let target = normalize_block_target(deps.as_ref(), target)?;
+    
+    if !BLOCKLIST.has(deps.storage, (&owner, &target.key())) {
+        return Err(ContractError::NotBlocked {});
+    }
+    BLOCKLIST.remove(deps.storage, (&owner, &target.key()));
+    
+    Ok(Response::new()
+        .add_attribute("method", "unblock")
+        .add_attribute("username", owner)
+        .add_attribute("target", target.key()))
+}
+
+fn execute_set_block_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy: BlockPolicy,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    BLOCK_POLICIES.save(deps.storage, &owner, &policy)?;
+    
+    let policy_name = match policy {
+        BlockPolicy::RejectTip => "reject_tip",
+        BlockPolicy::DropMessage => "drop_message",
+    };
+    
+    Ok(Response::new()
+        .add_attribute("method", "set_block_policy")
+        .add_attribute("username", owner)
+        .add_attribute("policy", policy_name))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_subscription_tier(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    price: Coin,
+    period_seconds: u64,
+    perks: Option<String>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let creator = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    ensure_not_suspended(deps.as_ref(), &creator)?;
+    
+    if name.trim().is_empty() {
+        return Err(ContractError::MissingField {
+            field: "name".to_string(),
+        });
+    }
+    if price.amount.is_zero() {
+        return Err(ContractError::MissingField {
+            field: "price".to_string(),
+        });
+    }
+    if period_seconds == 0 {
+        return Err(ContractError::MissingField {
+            field: "period_seconds".to_string(),
+        });
+    }
+    
+    let tier_id = SUBSCRIPTION_TIER_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SUBSCRIPTION_TIER_SEQ.save(deps.storage, &tier_id)?;
+    
+    let tier = SubscriptionTier {
+        id: tier_id,
+        creator: creator.clone(),
+        name,
+        price,
+        period_seconds,
+        perks,
+        active: true,
+        created_at: env.block.time,
+    };
+    SUBSCRIPTION_TIERS.save(deps.storage, (&creator, tier_id), &tier)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "create_subscription_tier")
+        .add_attribute("creator", creator)
+        .add_attribute("tier_id", tier_id.to_string())
+        .add_attribute("price", tier.price.to_string()))
+}
+
+fn execute_deactivate_subscription_tier(
+    deps: DepsMut,
+    info: MessageInfo,
+    tier_id: u64,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let creator = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    
+    // Existing subscriptions keep running; only new ones are refused
+    let mut tier = SUBSCRIPTION_TIERS
+        .may_load(deps.storage, (&creator, tier_id))?
+        .ok_or(ContractError::SubscriptionTierNotFound { tier_id })?;
+    tier.active = false;
+    SUBSCRIPTION_TIERS.save(deps.storage, (&creator, tier_id), &tier)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "deactivate_subscription_tier")
+        .add_attribute("creator", creator)
+        .add_attribute("tier_id", tier_id.to_string()))
+}
+
+fn execute_subscribe(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    creator: String,
+    tier_id: u64,
+    periods: u64,
+) -> Result<Response, ContractError> {
+    if !USER_PROFILES.has(deps.storage, &creator) {
+        return Err(ContractError::UsernameNotFound { username: creator });
+    }
+    ensure_not_suspended(deps.as_ref(), &creator)?;
+    
+    if holds_profile(deps.as_ref(), &info.sender, &creator)? {
+        return Err(ContractError::SelfTipping {});
+    }
+    if is_wallet_blocked(deps.as_ref(), &creator, &info.sender)? {
+        return Err(ContractError::SenderBlocked { username: creator });
+    }
+    
+    let tier = SUBSCRIPTION_TIERS
+        .may_load(deps.storage, (&creator, tier_id))?
+        .ok_or(ContractError::SubscriptionTierNotFound { tier_id })?;
+    if !tier.active {
+        return Err(ContractError::SubscriptionTierInactive { tier_id });
+    }
+    if periods == 0 {
+        return Err(ContractError::MissingField {
+            field: "periods".to_string(),
+        });
+    }
+    
+    // The whole prepayment must be attached
+    let total = tier
+        .price
+        .amount
+        .checked_mul(Uint128::from(periods))
+        .map_err(cosmwasm_std::StdError::from)?;
+    ensure_exact_funds(&info, &Coin::new(total.u128(), &tier.price.denom))?;
+    
+    let subscription_id = SUBSCRIPTION_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SUBSCRIPTION_SEQ.save(deps.storage, &subscription_id)?;
+    
+    let subscription = Subscription {
+        id: subscription_id,
+        subscriber: info.sender.clone(),
+        creator: creator.clone(),
+        tier_id,
+        price: tier.price,
+        period_seconds: tier.period_seconds,
+        started_at: env.block.time,
+        periods_paid: periods,
+        periods_claimed: 0,
+        cancelled_at: None,
+    };
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    CREATOR_SUBSCRIPTIONS.save(deps.storage, (&creator, subscription_id), &true)?;
+    SUBSCRIBER_SUBSCRIPTIONS.save(deps.storage, (&info.sender, subscription_id), &true)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "subscribe")
+        .add_attribute("subscriber", info.sender)
+        .add_attribute("creator", creator)
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("periods", periods.to_string()))
+}
+
+fn execute_cancel_subscription(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    let mut subscription = SUBSCRIPTIONS
+        .may_load(deps.storage, subscription_id)?
+        .ok_or(ContractError::SubscriptionNotFound { subscription_id })?;
+    
+    if subscription.subscriber != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if subscription.cancelled_at.is_some() {
+        return Err(ContractError::SubscriptionCancelled { subscription_id });
+    }
+    
+    // The period in progress is earned by the creator; later ones are refunded
+    let begun = (subscription.matured_periods(env.block.time) + 1).min(subscription.periods_paid);
+    let refunded_periods = subscription.periods_paid - begun;
+    subscription.periods_paid = begun;
+    subscription.cancelled_at = Some(env.block.time);
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    
+    let refund = subscription.price.amount * Uint128::from(refunded_periods);
+    let mut response = Response::new()
+        .add_attribute("method", "cancel_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("refunded_periods", refunded_periods.to_string());
+    
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(refund.u128(), &subscription.price.denom)],
+        });
+    }
+    
+    Ok(response)
+}
+
+fn execute_claim_subscriptions(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    username: Option<String>,
+) -> Result<Response, ContractError> {
+    let creator = resolve_sender_username(deps.as_ref(), &info.sender, username)?;
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let subscription_ids = CREATOR_SUBSCRIPTIONS
+        .prefix(&creator)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    // Sum the matured, unclaimed periods per denom, less the platform fee
+    let profile = USER_PROFILES.load(deps.storage, &creator)?;
+    let mut payouts: Vec<Coin> = Vec::new();
+    let mut referral_msgs = Vec::new();
+    let mut claimed_subscriptions = 0u64;
+    for subscription_id in subscription_ids {
+        let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+        let matured = subscription.matured_periods(env.block.time);
+        if matured <= subscription.periods_claimed {
+            continue;
+        }
+        
+        let amount = subscription.price.amount * Uint128::from(matured - subscription.periods_claimed);
+        let (net, referral_msg) = split_tip_funds(
+            deps.branch(),
+            env.block.time,
+            &profile,
+            Coin::new(amount.u128(), &subscription.price.denom),
+        )?;
+        add_coin(&mut payouts, net);
+        referral_msgs.extend(referral_msg);
+        subscription.periods_claimed = matured;
+        claimed_subscriptions += 1;
+        
+        // Fully settled subscriptions leave the creator's claim index
+        if subscription.periods_claimed == subscription.periods_paid {
+            CREATOR_SUBSCRIPTIONS.remove(deps.storage, (&creator, subscription_id));
+        }
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    }
+    
+    if claimed_subscriptions == 0 {
+        return Err(ContractError::NothingToClaim {});
+    }
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: payouts,
+        })
+        .add_messages(referral_msgs)
+        .add_attribute("method", "claim_subscriptions")
+        .add_attribute("creator", creator)
+        .add_attribute("claimed_subscriptions", claimed_subscriptions.to_string()))
+}
+
+fn execute_suspend_profile(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    // Only moderators can act on profiles and content
+    ensure_role(deps.as_ref(), &info.sender, Role::Moderator)?;
+    
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Err(ContractError::UsernameNotFound { username });
+    }
+    
+    let reason = validate_moderation_reason(reason)?;
+    
+    let suspension = ModerationAction {
+        reason: reason.clone(),
+        moderated_by: info.sender.clone(),
+        moderated_at: env.block.time,
+    };
+    SUSPENDED_PROFILES.save(deps.storage, &username, &suspension)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "suspend_profile")
+        .add_attribute("username", username)
+        .add_attribute("reason", reason)
+        .add_attribute("moderator", info.sender))
+}
+
+fn execute_unsuspend_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    // Only moderators can act on profiles and content
+    ensure_role(deps.as_ref(), &info.sender, Role::Moderator)?;
+    
+    if !SUSPENDED_PROFILES.has(deps.storage, &username) {
+        return Err(ContractError::ProfileNotSuspended { username });
+    }
+    
+    let reason = validate_moderation_reason(reason)?;
+    SUSPENDED_PROFILES.remove(deps.storage, &username);
+    
+    Ok(Response::new()
+        .add_attribute("method", "unsuspend_profile")
+        .add_attribute("username", username)
+        .add_attribute("reason", reason)
+        .add_attribute("moderator", info.sender))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_redact_tip_message(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_username: String,
+    to_username: String,
+    timestamp: Timestamp,
+    reason: String,
+) -> Result<Response, ContractError> {
+    // Only moderators can act on profiles and content
+    ensure_role(deps.as_ref(), &info.sender, Role::Moderator)?;
+    
+    let reason = validate_moderation_reason(reason)?;
+    
+    let tip_key = generate_tip_key(&from_username, &to_username, timestamp.nanos());
+    let mut tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_key)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    
+    // Drop the message but keep the amount so stats are unaffected
+    tip.message = None;
+    tip.encrypted_message = None;
+    tip.moderation = Some(ModerationAction {
+        reason: reason.clone(),
+        moderated_by: info.sender.clone(),
+        moderated_at: env.block.time,
+    });
+    TIP_RECORDS.save(deps.storage, &tip_key, &tip)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "redact_tip_message")
+        .add_attribute("tip_id", tip_key)
+        .add_attribute("reason", reason)
+        .add_attribute("moderator", info.sender))
+}
+
+fn execute_redact_tip_reply(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tip_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    // Only moderators can act on profiles and content
+    ensure_role(deps.as_ref(), &info.sender, Role::Moderator)?;
+    
+    let reason = validate_moderation_reason(reason)?;
+    
+    let mut tip = TIP_RECORDS
+        .may_load(deps.storage, &tip_id)?
+        .ok_or(ContractError::TipRecordNotFound {})?;
+    let reply = tip
+        .reply
+        .as_mut()
+        .ok_or_else(|| ContractError::ReplyNotFound { tip_id: tip_id.clone() })?;
+    
+    reply.message = None;
+    reply.moderation = Some(ModerationAction {
+        reason: reason.clone(),
+        moderated_by: info.sender.clone(),
+        moderated_at: env.block.time,
+    });
+    TIP_RECORDS.save(deps.storage, &tip_id, &tip)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "redact_tip_reply")
+        .add_attribute("tip_id", tip_id)
+        .add_attribute("reason", reason)
+        .add_attribute("moderator", info.sender))
+}
+
+fn execute_add_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: String,
+    roles: Vec<Role>,
+) -> Result<Response, ContractError> {
+    // Only owners can manage roles
+    ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+    
+    if roles.is_empty() {
+        return Err(ContractError::MissingField {
+            field: "roles".to_string(),
+        });
+    }
+    
+    if roles.contains(&Role::Owner) {
+        return Err(ContractError::OwnerGrantNotAllowed {});
+    }
+    
+    // Validate and merge the new roles into the existing ones
+    let admin_addr = addr_validate(deps.api, &admin)?;
+    let mut current = ADMIN_ROLES.may_load(deps.storage, &admin_addr)?.unwrap_or_default();
+    let mut granted = Vec::new();
+    for role in roles {
+        if !current.contains(&role) {
+            current.push(role);
+            granted.push(role);
+        }
+    }
+    current.sort();
+    ADMIN_ROLES.save(deps.storage, &admin_addr, &current)?;
+    
+    let events = granted
+        .iter()
+        .map(|role| role_change_event("grant", &admin_addr, *role, &info.sender));
+    
+    Ok(Response::new()
+        .add_attribute("method", "add_admin")
+        .add_attribute("admin", admin)
+        .add_events(events))
+}
+
+fn execute_remove_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: String,
+    roles: Option<Vec<Role>>,
+) -> Result<Response, ContractError> {
+    // Only owners can manage roles
+    ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+    
+    // Validate admin address
+    let admin_addr = addr_validate(deps.api, &admin)?;
+    if admin_addr == info.sender {
+        return Err(ContractError::SelfRemoval {});
+    }
+    
+    // The target must actually be an admin
+    let current = ADMIN_ROLES
+        .may_load(deps.storage, &admin_addr)?
+        .ok_or_else(|| ContractError::AdminNotFound {
+            address: admin_addr.to_string(),
+        })?;
+    
+    // Every explicitly revoked role must be held
+    if let Some(roles) = &roles {
+        if roles.is_empty() {
+            return Err(ContractError::MissingField {
+                field: "roles".to_string(),
+            });
+        }
+        if let Some(role) = roles.iter().find(|role| !current.contains(role)) {
+            return Err(ContractError::RoleNotHeld {
+                address: admin_addr.to_string(),
+                role: role.to_string(),
+            });
+        }
+    }
+    
+    // Split the current roles into the ones kept and the ones revoked
+    let (revoked, kept): (Vec<Role>, Vec<Role>) = current
+        .into_iter()
+        .partition(|role| roles.as_ref().is_none_or(|r| r.contains(role)));
+    
+    let events = revoke_roles(deps, &admin_addr, revoked, kept, &info.sender)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "remove_admin")
+        .add_attribute("admin", admin)
+        .add_events(events))
+}
+
+fn execute_renounce_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let current = ADMIN_ROLES
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::AdminNotFound {
+            address: info.sender.to_string(),
+        })?;
+    
+    let events = revoke_roles(deps, &info.sender, current, Vec::new(), &info.sender)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "renounce_admin")
+        .add_attribute("admin", info.sender)
+        .add_events(events))
+}
+
+fn execute_propose_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+    
+    let new_owner_addr = addr_validate(deps.api, &new_owner)?;
+    if new_owner_addr == info.sender {
+        return Err(ContractError::CustomError {
+            message: "Cannot propose yourself as owner".to_string(),
+        });
+    }
+    
+    // A new proposal replaces any pending one
+    let proposal = OwnershipProposal {
+        proposer: info.sender.clone(),
+        new_owner: new_owner_addr,
+        proposed_at: env.block.time,
+    };
+    PENDING_OWNERSHIP.save(deps.storage, &proposal)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "propose_ownership")
+        .add_attribute("proposer", info.sender)
+        .add_attribute("new_owner", new_owner))
+}
+
+fn execute_accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let proposal = PENDING_OWNERSHIP
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoOwnershipProposal {})?;
+    
+    if proposal.new_owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    // The proposer must still be an owner for the handover to be valid
+    let mut proposer_roles = ADMIN_ROLES
+        .may_load(deps.storage, &proposal.proposer)?
+        .unwrap_or_default();
+    if !proposer_roles.contains(&Role::Owner) {
+        return Err(ContractError::NoOwnershipProposal {});
+    }
+    
+    // Move the owner role; the proposer keeps any other roles
+    proposer_roles.retain(|role| *role != Role::Owner);
+    if proposer_roles.is_empty() {
+        ADMIN_ROLES.remove(deps.storage, &proposal.proposer);
+    } else {
+        ADMIN_ROLES.save(deps.storage, &proposal.proposer, &proposer_roles)?;
+    }
+    
+    let mut new_roles = ADMIN_ROLES.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if !new_roles.contains(&Role::Owner) {
+        new_roles.push(Role::Owner);
+        new_roles.sort();
+    }
+    ADMIN_ROLES.save(deps.storage, &info.sender, &new_roles)?;
+    PENDING_OWNERSHIP.remove(deps.storage);
+    
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("previous_owner", proposal.proposer.to_string())
+        .add_attribute("new_owner", info.sender.to_string())
+        .add_event(role_change_event("revoke", &proposal.proposer, Role::Owner, &info.sender))
+        .add_event(role_change_event("grant", &info.sender, Role::Owner, &info.sender)))
+}
+
+fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+    
+    if !PENDING_OWNERSHIP.exists(deps.storage) {
+        return Err(ContractError::NoOwnershipProposal {});
+    }
+    PENDING_OWNERSHIP.remove(deps.storage);
+    
+    Ok(Response::new()
+        .add_attribute("method", "cancel_ownership_proposal")
+        .add_attribute("cancelled_by", info.sender))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.as_ref(), &info.sender, Role::ConfigAdmin)?;
+    
+    let mut config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(timeout) = update.escrow_timeout_seconds {
+        config.escrow_timeout_seconds = timeout;
+    }
+    if let Some(expiry) = update.unregistered_tip_expiry_seconds {
+        config.unregistered_tip_expiry_seconds = expiry;
+    }
+    if let Some(window) = update.refund_request_window_seconds {
+        config.refund_request_window_seconds = window;
+    }
+    if let Some(bps) = update.keeper_reward_bps {
+        config.keeper_reward_bps = validate_bps("keeper_reward_bps", bps)?;
+    }
+    if let Some(bps) = update.platform_fee_bps {
+        config.platform_fee_bps = validate_bps("platform_fee_bps", bps)?;
+    }
+    if let Some(bps) = update.referral_share_bps {
+        config.referral_share_bps = validate_bps("referral_share_bps", bps)?;
+    }
+    if let Some(period) = update.referral_period_seconds {
+        config.referral_period_seconds = period;
+    }
+    if let Some(nft_contract) = update.receipt_nft_contract {
+        config.receipt_nft_contract = Some(addr_validate(deps.api, &nft_contract)?);
+    }
+    if let Some(nft_contract) = update.profile_nft_contract {
+        // Minted names resolve their owner against this collection, so it is fixed once in use
+        let nft_contract = addr_validate(deps.api, &nft_contract)?;
+        let minted = PROFILE_NFTS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if minted && config.profile_nft_contract.as_ref() != Some(&nft_contract) {
+            return Err(ContractError::ProfileNftContractLocked {});
+        }
+        config.profile_nft_contract = Some(nft_contract);
+    }
+    if let Some(bps) = update.marketplace_fee_bps {
+        config.marketplace_fee_bps = validate_bps("marketplace_fee_bps", bps)?;
+    }
+    if let Some(fee) = update.registration_fee {
+        config.registration_fee = if fee.amount.is_zero() { None } else { Some(fee) };
+    }
+    if let Some(period) = update.registration_period_seconds {
+        config.registration_period_seconds = period;
+    }
+    if let Some(grace) = update.registration_grace_seconds {
+        config.registration_grace_seconds = grace;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "update_config")
+        .add_attribute("escrow_timeout_seconds", config.escrow_timeout_seconds.to_string())
+        .add_attribute(
+            "unregistered_tip_expiry_seconds",
+            config.unregistered_tip_expiry_seconds.to_string(),
+        )
+        .add_attribute("keeper_reward_bps", config.keeper_reward_bps.to_string())
+        .add_attribute(
+            "refund_request_window_seconds",
+            config.refund_request_window_seconds.to_string(),
+        )
+        .add_attribute("platform_fee_bps", config.platform_fee_bps.to_string())
+        .add_attribute("referral_share_bps", config.referral_share_bps.to_string())
+        .add_attribute("referral_period_seconds", config.referral_period_seconds.to_string())
+        .add_attribute(
+            "registration_period_seconds",
+            config.registration_period_seconds.to_string(),
+        )
+        .add_attribute("registration_grace_seconds", config.registration_grace_seconds.to_string())
+        .add_attribute("changed_by", info.sender))
+}
+
+fn execute_withdraw_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.as_ref(), &info.sender, Role::Treasurer)?;
+    
+    let recipient = match recipient {
+        Some(recipient) => addr_validate(deps.api, &recipient)?,
+        None => info.sender.clone(),
+    };
+    
+    let fees = PLATFORM_FEES.may_load(deps.storage)?.unwrap_or_default();
+    if fees.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    PLATFORM_FEES.remove(deps.storage);
+    
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: fees,
+        })
+        .add_attribute("method", "withdraw_fees")
+        .add_attribute("recipient", recipient)
+        .add_attribute("treasurer", info.sender))
+}
+
+fn execute_set_pause_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    registrations: Option<bool>,
+    profile_updates: Option<bool>,
+    tips: Option<bool>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.as_ref(), &info.sender, Role::ConfigAdmin)?;
+    
+    let mut status = PAUSE_STATUS.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(paused) = registrations {
+        status.registrations = paused;
+    }
+    if let Some(paused) = profile_updates {
+        status.profile_updates = paused;
+    }
+    if let Some(paused) = tips {
+        status.tips = paused;
+    }
+    PAUSE_STATUS.save(deps.storage, &status)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "set_pause_status")
+        .add_attribute("registrations", status.registrations.to_string())
+        .add_attribute("profile_updates", status.profile_updates.to_string())
+        .add_attribute("tips", status.tips.to_string())
+        .add_attribute("changed_by", info.sender))
+}
+
+fn execute_verify_profile(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    username: String,
+    category: String,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    // Only verifiers can issue badges
+    ensure_role(deps.as_ref(), &info.sender, Role::Verifier)?;
+    
+    // Check if profile exists
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Err(ContractError::UsernameNotFound { username });
+    }
+    
+    if category.trim().is_empty() {
+        return Err(ContractError::MissingField {
+            field: "category".to_string(),
+        });
+    }
+    
+    if let Some(expiry) = expires_at {
+        if expiry <= env.block.time {
+            return Err(ContractError::InvalidExpiration {});
+        }
+    }
+    
+    // Issuing a new badge replaces any previous one
+    let verification = Verification {
+        category: category.clone(),
+        verified_by: info.sender.clone(),
+        verified_at: env.block.time,
+        expires_at,
+    };
+    PROFILE_VERIFICATIONS.save(deps.storage, &username, &verification)?;
+    
+    let expires_at = expires_at
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| "never".to_string());
+    
+    Ok(Response::new()
+        .add_attribute("method", "verify_profile")
+        .add_attribute("username", username)
+        .add_attribute("category", category)
+        .add_attribute("expires_at", expires_at)
+        .add_attribute("verified_by", info.sender))
+}
+
+fn execute_revoke_verification(
+    deps: DepsMut,
+    info: MessageInfo,
+    username: String,
+) -> Result<Response, ContractError> {
+    // Only verifiers can revoke badges
+    ensure_role(deps.as_ref(), &info.sender, Role::Verifier)?;
+    
+    if !PROFILE_VERIFICATIONS.has(deps.storage, &username) {
+        return Err(ContractError::VerificationNotFound { username });
+    }
+    
+    PROFILE_VERIFICATIONS.remove(deps.storage, &username);
+    
+    Ok(Response::new()
+        .add_attribute("method", "revoke_verification")
+        .add_attribute("username", username)
+        .add_attribute("revoked_by", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetProfile { username } => to_json_binary(&query_profile(deps, env, username)?),
+        QueryMsg::GetProfileByWallet { wallet } => to_json_binary(&query_profile_by_wallet(deps, env, wallet)?),
+        QueryMsg::GetProfilesByWallet { wallet } => to_json_binary(&query_profiles_by_wallet(deps, wallet)?),
+        QueryMsg::GetProfileManagers { username } => to_json_binary(&query_profile_managers(deps, username)?),
+        QueryMsg::ListProfiles { limit, start_after, verified_only } => to_json_binary(&query_list_profiles(deps, env, limit, start_after, verified_only)?),
+        QueryMsg::GetTipsSent { username, limit, start_after } => to_json_binary(&query_tips_sent(deps, username, limit, start_after)?),
+        QueryMsg::GetTipsReceived { username, limit, start_after } => to_json_binary(&query_tips_received(deps, username, limit, start_after)?),
+        QueryMsg::GetTipWall { username, limit, start_after } => to_json_binary(&query_tip_wall(deps, username, limit, start_after)?),
+        QueryMsg::GetDeposits { wallet } => to_json_binary(&DepositsResponse {
+            balances: DEPOSITS
+                .may_load(deps.storage, &deps.api.addr_validate(&wallet)?)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::GetTipAllowance { granter, delegate } => to_json_binary(&query_tip_allowance(deps, env, granter, delegate)?),
+        QueryMsg::GetTipAllowances { granter, start_after, limit } => to_json_binary(&query_tip_allowances(deps, env, granter, start_after, limit)?),
+        QueryMsg::GetListing { username } => to_json_binary(&ListingResponse {
+            listing: LISTINGS.may_load(deps.storage, &username)?,
+        }),
+        QueryMsg::GetListings { start_after, limit } => to_json_binary(&query_listings(deps, start_after, limit)?),
+        QueryMsg::GetOffers { username, start_after, limit } => to_json_binary(&query_offers(deps, username, start_after, limit)?),
+        QueryMsg::GetTipReceipt { tip_id } => to_json_binary(&TipReceiptResponse {
+            token_id: TIP_RECEIPTS.may_load(deps.storage, &tip_id)?,
+        }),
+        QueryMsg::GetReceiptThreshold { username } => to_json_binary(&ReceiptThresholdResponse {
+            threshold: RECEIPT_THRESHOLDS.may_load(deps.storage, &username)?,
+        }),
+        QueryMsg::GetPendingTips { username, limit, start_after } => to_json_binary(&query_pending_tips(deps, username, limit, start_after)?),
+        QueryMsg::GetRefundRequests { username, limit, start_after } => to_json_binary(&query_refund_requests(deps, username, limit, start_after)?),
+        QueryMsg::GetUnregisteredNameTips { username } => to_json_binary(&query_unregistered_name_tips(deps, username)?),
+        QueryMsg::GetTipDetail { from_username, to_username, timestamp } => to_json_binary(&query_tip_detail(deps, from_username, to_username, timestamp)?),
+        QueryMsg::GetSubscriptionTiers { creator } => to_json_binary(&query_subscription_tiers(deps, creator)?),
+        QueryMsg::GetSubscribers { creator, start_after, limit } => to_json_binary(&query_subscribers(deps, env, creator, start_after, limit)?),
+        QueryMsg::GetSubscriptions { subscriber, start_after, limit } => to_json_binary(&query_subscriptions(deps, subscriber, start_after, limit)?),
+        QueryMsg::GetGoals { username } => to_json_binary(&query_goals(deps, env, username)?),
+        QueryMsg::GetCampaign { campaign_id } => to_json_binary(&query_campaign(deps, env, campaign_id)?),
+        QueryMsg::GetCampaigns { username, start_after, limit } => to_json_binary(&query_campaigns(deps, env, username, start_after, limit)?),
+        QueryMsg::GetPledge { campaign_id, pledger } => to_json_binary(&query_pledge(deps, campaign_id, pledger)?),
+        QueryMsg::GetStream { stream_id } => to_json_binary(&query_stream(deps, env, stream_id)?),
+        QueryMsg::GetStreamsSent { username, start_after, limit } => to_json_binary(&query_streams(deps, STREAMS_SENT, username, start_after, limit)?),
+        QueryMsg::GetStreamsReceived { username, start_after, limit } => to_json_binary(&query_streams(deps, STREAMS_RECEIVED, username, start_after, limit)?),
+        QueryMsg::GetScheduledTip { schedule_id } => to_json_binary(&ScheduledTipResponse {
+            schedule: SCHEDULED_TIPS.may_load(deps.storage, schedule_id)?,
+        }),
+        QueryMsg::GetScheduledTipsSent { username, start_after, limit } => to_json_binary(&query_scheduled_tips_sent(deps, username, start_after, limit)?),
+        QueryMsg::GetDueScheduledTips { limit } => to_json_binary(&query_due_scheduled_tips(deps, env, limit)?),
+        QueryMsg::GetBlocked { username, start_after, limit } => to_json_binary(&query_blocked(deps, username, start_after, limit)?),
+        QueryMsg::GetUserStats { username } => to_json_binary(&query_user_stats(deps, username)?),
+        QueryMsg::IsAdmin { address } => to_json_binary(&query_is_admin(deps, address)?),
+        QueryMsg::IsVerifier { address } => to_json_binary(&query_is_verifier(deps, address)?),
+        QueryMsg::GetRoles { address } => to_json_binary(&query_roles(deps, address)?),
+        QueryMsg::ListAdmins { limit, start_after } => to_json_binary(&query_list_admins(deps, limit, start_after)?),
+        QueryMsg::GetOwnershipProposal {} => to_json_binary(&OwnershipProposalResponse {
+            proposal: PENDING_OWNERSHIP.may_load(deps.storage)?,
+        }),
+        QueryMsg::GetPauseStatus {} => to_json_binary(&query_pause_status(deps)?),
+        QueryMsg::GetPlatformFees {} => to_json_binary(&PlatformFeesResponse {
+            fees: PLATFORM_FEES.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::GetReferrals { username, start_after, limit } => to_json_binary(&query_referrals(deps, username, start_after, limit)?),
+        QueryMsg::GetConfig {} => to_json_binary(&ConfigResponse {
+            config: CONFIG.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::IsUsernameAvailable { username } => to_json_binary(&query_is_username_available(deps, env, username)?),
+        QueryMsg::GetArchivedProfiles { username, start_after, limit } => to_json_binary(&query_archived_profiles(deps, username, start_after, limit)?),
+    }
+}
+
+fn query_profile(deps: Deps, env: Env, username: String) -> StdResult<ProfileResponse> {
+    let profile = USER_PROFILES.may_load(deps.storage, &username)?;
+    build_profile_response(deps, &env, profile)
+}
+
+fn query_profiles_by_wallet(deps: Deps, wallet: String) -> StdResult<ProfilesResponse> {
+    let addr = deps.api.addr_validate(&wallet)?;
+    
+    // Profiles registered before wallets could own several are only known as the primary
+    let mut usernames: Vec<String> = WALLET_TO_USERNAME.may_load(deps.storage, &addr)?.into_iter().collect();
+    for username in WALLET_PROFILES.prefix(&addr).keys(deps.storage, None, None, Order::Ascending) {
+        let username = username?;
+        if !usernames.contains(&username) {
+            usernames.push(username);
+        }
+    }
+    
+    let profiles = usernames
+        .iter()
+        .map(|username| USER_PROFILES.load(deps.storage, username))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ProfilesResponse { profiles })
+}
+
+fn query_profile_managers(deps: Deps, username: String) -> StdResult<ProfileManagersResponse> {
+    let managers = PROFILE_MANAGERS
+        .prefix(&username)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ProfileManagersResponse { managers })
+}
+
+fn query_profile_by_wallet(deps: Deps, env: Env, wallet: String) -> StdResult<ProfileResponse> {
+    let addr = deps.api.addr_validate(&wallet)?;
+    
+    // Get username associated with this wallet
+    let username = match WALLET_TO_USERNAME.may_load(deps.storage, &addr)? {
+        Some(username) => username,
+        None => return build_profile_response(deps, &env, None),
+    };
+    
+    let profile = USER_PROFILES.may_load(deps.storage, &username)?;
+    build_profile_response(deps, &env, profile)
+}
+
+// Attach verification status to a profile lookup
+fn build_profile_response(
+    deps: Deps,
+    env: &Env,
+    profile: Option<UserProfile>,
+) -> StdResult<ProfileResponse> {
+    let verification = match &profile {
+        Some(p) => PROFILE_VERIFICATIONS.may_load(deps.storage, &p.username)?,
+        None => None,
+    };
+    let is_verified = verification
+        .as_ref()
+        .map(|v| v.is_active(env.block.time))
+        .unwrap_or(false);
+    let suspension = match &profile {
+        Some(p) => SUSPENDED_PROFILES.may_load(deps.storage, &p.username)?,
+        None => None,
+    };
+    
+    Ok(ProfileResponse {
+        profile,
+        verification,
+        is_verified,
+        suspension,
+    })
+}
+
+fn query_list_profiles(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+    start_after: Option<String>,
+    verified_only: Option<bool>,
+) -> StdResult<ProfilesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    
+    // Get all profiles
+    let all_profiles: StdResult<Vec<UserProfile>> = USER_PROFILES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_, profile) = item?;
+            Ok(profile)
+        })
+        .collect();
+    
+    let verified_only = verified_only.unwrap_or(false);
+    
+    // Filter and limit profiles
+    let profiles = all_profiles?
+        .into_iter()
+        .filter(|p| if let Some(ref start) = start_after {
+            p.username > *start
+        } else {
+            true
+        })
+        .filter(|p| !verified_only || is_verified(deps, &env, &p.username))
+        .take(limit)
+        .collect();
+    
+    Ok(ProfilesResponse { profiles })
+}
+
+fn query_tips_sent(
+    deps: Deps,
+    username: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<TipsResponse> {
+    // Check if user exists
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Ok(TipsResponse { tips: Vec::new() });
+    }
+    
+    let limit = limit.unwrap_or(30) as usize;
+    
+    // Get all tip references for this sender
+    let tip_refs = TIPS_SENT.may_load(deps.storage, &username)?.unwrap_or_default();
+    
+    // Filter by start_after if provided
+    let filtered_refs = if let Some(after_key) = start_after {
+        tip_refs
+            .into_iter()
+            .filter(|(_, key)| key > &after_key)
+            .collect::<Vec<_>>()
+    } else {
+        tip_refs
+    };
+    
+    // Sort by key (which contains timestamp) - newest first
+    let mut sorted_refs = filtered_refs;
+    sorted_refs.sort_by(|a, b| b.1.cmp(&a.1));
+    
+    // Take up to limit
+    let limited_refs = sorted_refs.into_iter().take(limit).collect::<Vec<_>>();
+    
+    // Retrieve the actual tip records
+    let mut tips = Vec::new();
+    for (_, tip_key) in limited_refs {
+        if let Ok(tip) = TIP_RECORDS.load(deps.storage, &tip_key) {
+            tips.push(tip);
+        }
+    }
+    
+    Ok(TipsResponse { tips })
+}
+
+fn query_tips_received(
+    deps: Deps,
+    username: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<TipsResponse> {
+    // Check if user exists
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Ok(TipsResponse { tips: Vec::new() });
+    }
+    
+    let limit = limit.unwrap_or(30) as usize;
+    
+    // Get all tip references for this recipient
+    let tip_refs = TIPS_RECEIVED.may_load(deps.storage, &username)?.unwrap_or_default();
+    
+    // Filter by start_after if provided
+    let filtered_refs = if let Some(after_key) = start_after {
+        tip_refs
+            .into_iter()
+            .filter(|(_, key)| key > &after_key)
+            .collect::<Vec<_>>()
+    } else {
+        tip_refs
+    };
+    
+    // Sort by key (which contains timestamp) - newest first
+    let mut sorted_refs = filtered_refs;
+    sorted_refs.sort_by(|a, b| b.1.cmp(&a.1));
+    
+    // Take up to limit
+    let limited_refs = sorted_refs.into_iter().take(limit).collect::<Vec<_>>();
+    
+    // Retrieve the actual tip records
+    let mut tips = Vec::new();
+    for (_, tip_key) in limited_refs {
+        if let Ok(tip) = TIP_RECORDS.load(deps.storage, &tip_key) {
+            tips.push(tip);
+        }
+    }
+    
+    Ok(TipsResponse { tips })
+}
+
+fn query_tip_allowance(
+    deps: Deps,
+    env: Env,
+    granter: String,
+    delegate: String,
+) -> StdResult<TipAllowanceResponse> {
+    let granter = deps.api.addr_validate(&granter)?;
+    let delegate = deps.api.addr_validate(&delegate)?;
+    let allowance = TIP_ALLOWANCES
+        .may_load(deps.storage, (&granter, &delegate))?
+        .map(|mut allowance| {
+            roll_allowance_period(&mut allowance, env.block.time);
+            allowance
+        });
+    
+    Ok(TipAllowanceResponse { allowance })
+}
+
+fn query_tip_allowances(
+    deps: Deps,
+    env: Env,
+    granter: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TipAllowancesResponse> {
+    let granter = deps.api.addr_validate(&granter)?;
+    let start_after = start_after.map(|delegate| deps.api.addr_validate(&delegate)).transpose()?;
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    
+    let allowances = TIP_ALLOWANCES
+        .prefix(&granter)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(_, mut allowance)| {
+                roll_allowance_period(&mut allowance, env.block.time);
+                allowance
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(TipAllowancesResponse { allowances })
+}
+
+fn query_listings(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    
+    let listings = LISTINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ListingsResponse { listings })
+}
+
+fn query_offers(
+    deps: Deps,
+    username: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let offers = OFFERS
+        .prefix(&username)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, offer)| offer))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(OffersResponse { offers })
+}
+
+fn query_tip_wall(
+    deps: Deps,
+    username: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<TipWallResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let shown = |tip: &TipRecord| tip.visibility.is_public() && tip.status.counts_towards_totals();
+    
+    // Pinned tips lead the first page only
+    let mut pinned = Vec::new();
+    if start_after.is_none() {
+        for tip_key in PINNED_TIPS
+            .prefix(&username)
+            .keys(deps.storage, None, None, Order::Ascending)
+        {
+            let tip = TIP_RECORDS.load(deps.storage, &tip_key?)?;
+            if shown(&tip) {
+                pinned.push(tip);
+            }
+        }
+        pinned.sort_by_key(|tip| std::cmp::Reverse(tip.timestamp));
+    }
+    
+    // Newest first by tip time, continuing below `start_after`
+    let after = match &start_after {
+        Some(tip_key) => Some((TIP_RECORDS.load(deps.storage, tip_key)?.timestamp.nanos(), tip_key.as_str())),
+        None => None,
+    };
+    
+    let mut tips = Vec::new();
+    for item in TIPS_RECEIVED_BY_TIME.sub_prefix(&username).keys(
+        deps.storage,
+        None,
+        after.map(Bound::exclusive),
+        Order::Descending,
+    ) {
+        if tips.len() >= limit {
+            break;
+        }
+        let (_, tip_key) = item?;
+        let tip = TIP_RECORDS.load(deps.storage, &tip_key)?;
+        if shown(&tip) && !tip.visibility.pinned {
+            tips.push(tip);
+        }
+    }
+    
+    Ok(TipWallResponse { pinned, tips })
+}
+
+fn query_pending_tips(
+    deps: Deps,
+    username: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<TipsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    
+    let tips = PENDING_TIPS
+        .prefix(&username)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|tip_key| TIP_RECORDS.load(deps.storage, &tip_key?))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(TipsResponse { tips })
+}
+
+fn query_refund_requests(
+    deps: Deps,
+    username: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<RefundRequestsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    
+    let requests = REFUND_REQUESTS
+        .prefix(&username)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, request)| request))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(RefundRequestsResponse { requests })
+}
+
+fn query_unregistered_name_tips(
+    deps: Deps,
+    username: String,
+) -> StdResult<UnregisteredNameTipsResponse> {
+    let tips = UNREGISTERED_NAME_TIPS
+        .prefix(&username)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, tip)| tip))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(UnregisteredNameTipsResponse { tips })
+}
+
+fn query_tip_detail(
+    deps: Deps,
+    from_username: String,
+    to_username: String,
+    timestamp: cosmwasm_std::Timestamp,
+) -> StdResult<TipDetailResponse> {
+    let key = format!("{}:{}:{}", from_username, to_username, timestamp.nanos());
+    let tip = TIP_RECORDS.may_load(deps.storage, &key)?;
+    Ok(TipDetailResponse { tip })
+}
+
+fn query_subscription_tiers(deps: Deps, creator: String) -> StdResult<SubscriptionTiersResponse> {
+    let tiers = SUBSCRIPTION_TIERS
+        .prefix(&creator)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, tier)| tier))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SubscriptionTiersResponse { tiers })
+}
+
+fn query_subscribers(
+    deps: Deps,
+    env: Env,
+    creator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SubscriptionsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    // Only subscriptions still granting membership are listed
+    let mut subscriptions = Vec::new();
+    for subscription_id in CREATOR_SUBSCRIPTIONS
+        .prefix(&creator)
+        .keys(deps.storage, start, None, Order::Ascending)
+    {
+        let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id?)?;
+        if subscription.is_active(env.block.time) {
+            subscriptions.push(subscription);
+            if subscriptions.len() >= limit {
+                break;
+            }
+        }
+    }
+    
+    Ok(SubscriptionsResponse { subscriptions })
+}
+
+fn query_subscriptions(
+    deps: Deps,
+    subscriber: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SubscriptionsResponse> {
+    let subscriber = deps.api.addr_validate(&subscriber)?;
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let subscriptions = SUBSCRIBER_SUBSCRIPTIONS
+        .prefix(&subscriber)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| SUBSCRIPTIONS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(SubscriptionsResponse { subscriptions })
+}
+
+fn query_goals(deps: Deps, env: Env, username: String) -> StdResult<GoalsResponse> {
+    let goals = GOALS
+        .prefix(&username)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, goal) = item?;
+            let status = goal.status(env.block.time);
+            Ok(GoalInfo { goal, status })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(GoalsResponse { goals })
+}
+
+fn query_campaign(deps: Deps, env: Env, campaign_id: u64) -> StdResult<CampaignResponse> {
+    let campaign = CAMPAIGNS
+        .may_load(deps.storage, campaign_id)?
+        .map(|campaign| CampaignInfo {
+            status: campaign.status(env.block.time),
+            campaign,
+        });
+    Ok(CampaignResponse { campaign })
+}
+
+fn query_campaigns(
+    deps: Deps,
+    env: Env,
+    username: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<CampaignsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let campaigns = CREATOR_CAMPAIGNS
+        .prefix(&username)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| {
+            let campaign = CAMPAIGNS.load(deps.storage, id?)?;
+            Ok(CampaignInfo {
+                status: campaign.status(env.block.time),
+                campaign,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(CampaignsResponse { campaigns })
+}
+
+fn query_pledge(deps: Deps, campaign_id: u64, pledger: String) -> StdResult<PledgeResponse> {
+    let pledger = deps.api.addr_validate(&pledger)?;
+    let amount = CAMPAIGN_PLEDGES
+        .may_load(deps.storage, (campaign_id, &pledger))?
+        .unwrap_or_default();
+    Ok(PledgeResponse { amount })
+}
+
+fn query_stream(deps: Deps, env: Env, stream_id: u64) -> StdResult<StreamResponse> {
+    let stream = TIP_STREAMS.may_load(deps.storage, stream_id)?;
+    let vested = stream
+        .as_ref()
+        .map(|s| s.vested(env.block.time))
+        .unwrap_or_default();
+    let withdrawable = stream
+        .as_ref()
+        .map(|s| vested - s.withdrawn)
+        .unwrap_or_default();
+    Ok(StreamResponse {
+        stream,
+        vested,
+        withdrawable,
+    })
+}
+
+fn query_streams(
+    deps: Deps,
+    index: cw_storage_plus::Map<(&str, u64), bool>,
+    username: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<StreamsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let streams = index
+        .prefix(&username)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| TIP_STREAMS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(StreamsResponse { streams })
+}
+
+fn query_scheduled_tips_sent(
+    deps: Deps,
+    username: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ScheduledTipsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let schedules = SCHEDULED_TIPS_SENT
+        .prefix(&username)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| SCHEDULED_TIPS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ScheduledTipsResponse { schedules })
+}
+
+fn query_due_scheduled_tips(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+) -> StdResult<ScheduledTipsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let end = Bound::inclusive((env.block.time.seconds(), u64::MAX));
+    
+    let schedules = DUE_SCHEDULED_TIPS
+        .keys(deps.storage, None, Some(end), Order::Ascending)
+        .take(limit)
+        .map(|key| SCHEDULED_TIPS.load(deps.storage, key?.1))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ScheduledTipsResponse { schedules })
+}
+
+fn query_blocked(
+    deps: Deps,
+    username: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BlockedResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    
+    let blocked = BLOCKLIST
+        .prefix(&username)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+    let policy = BLOCK_POLICIES.may_load(deps.storage, &username)?.unwrap_or_default();
+    
+    Ok(BlockedResponse { policy, blocked })
+}
+
+fn query_user_stats(deps: Deps, username: String) -> StdResult<StatsResponse> {
+    // Check if user exists
+    if !USER_PROFILES.has(deps.storage, &username) {
+        return Ok(StatsResponse {
+            total_tips_sent: 0,
+            total_tips_received: 0,
+            total_amount_sent: "0uxion".to_string(),
+            total_amount_received: "0uxion".to_string(),
+            referral_earnings: Vec::new(),
+        });
+    }
+    
+    // Get all tips sent
+    let tips_sent = TIPS_SENT.may_load(deps.storage, &username)?.unwrap_or_default();
+    
+    // Get all tips received
+    let tips_received = TIPS_RECEIVED.may_load(deps.storage, &username)?.unwrap_or_default();
+    
+    // Calculate totals sent, skipping tips whose funds went back to the sender
+    let mut total_tips_sent = 0u64;
+    let mut total_amount_sent = 0u128;
+    for (_, tip_key) in &tips_sent {
+        if let Ok(tip) = TIP_RECORDS.load(deps.storage, tip_key) {
+            if !tip.status.counts_towards_totals() {
+                continue;
+            }
+            total_tips_sent += 1;
+            if let Some(amount_str) = tip.amount.strip_suffix("uxion") {
+                if let Ok(amount) = amount_str.parse::<u128>() {
+                    total_amount_sent += amount;
+                }
+            }
+        }
+    }
+    
+    // Calculate totals received, skipping tips whose funds went back to the sender
+    let mut total_tips_received = 0u64;
+    let mut total_amount_received = 0u128;
+    for (_, tip_key) in &tips_received {
+        if let Ok(tip) = TIP_RECORDS.load(deps.storage, tip_key) {
+            if !tip.status.counts_towards_totals() {
+                continue;
+            }
+            total_tips_received += 1;
+            if let Some(amount_str) = tip.amount.strip_suffix("uxion") {
+                if let Ok(amount) = amount_str.parse::<u128>() {
+                    total_amount_received += amount;
+                }
+            }
+        }
+    }
+    
+    Ok(StatsResponse {
+        total_tips_sent,
+        total_tips_received,
         total_amount_sent: format!("{}uxion", total_amount_sent),
         total_amount_received: format!("{}uxion", total_amount_received),
+        referral_earnings: REFERRAL_EARNINGS.may_load(deps.storage, &username)?.unwrap_or_default(),
+    })
+}
+
+fn query_referrals(
+    deps: Deps,
+    username: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReferralsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    
+    let referrals = REFERRALS
+        .prefix(&username)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|referred| {
+            let profile = USER_PROFILES.load(deps.storage, &referred?)?;
+            Ok(ReferralInfo {
+                registered_at: profile.created_at,
+                referral_ends_at: profile.created_at.plus_seconds(config.referral_period_seconds),
+                username: profile.username,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ReferralsResponse {
+        referrals,
+        earnings: REFERRAL_EARNINGS.may_load(deps.storage, &username)?.unwrap_or_default(),
+    })
+}
+
+fn query_is_admin(deps: Deps, address: String) -> StdResult<AdminResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let is_admin = ADMIN_ROLES.has(deps.storage, &addr);
+    Ok(AdminResponse { is_admin })
+}
+
+fn query_is_verifier(deps: Deps, address: String) -> StdResult<VerifierResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let is_verifier = has_role(deps, &addr, Role::Verifier)?;
+    Ok(VerifierResponse { is_verifier })
+}
+
+fn query_roles(deps: Deps, address: String) -> StdResult<RolesResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let roles = ADMIN_ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(RolesResponse { roles })
+}
+
+fn query_list_admins(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<AdminsResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start_addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+    
+    let admins = ADMIN_ROLES
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, roles) = item?;
+            Ok(AdminInfo {
+                address: address.to_string(),
+                roles,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(AdminsResponse { admins })
+}
+
+fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    let status = PAUSE_STATUS.may_load(deps.storage)?.unwrap_or_default();
+    let any_paused = status.registrations || status.profile_updates || status.tips;
+    Ok(PauseStatusResponse { status, any_paused })
+}
+
+fn query_is_username_available(deps: Deps, env: Env, username: String) -> StdResult<UsernameAvailableResponse> {
+    // Check if valid username format
+    let is_valid = validate_username(&username).is_ok();
+    
+    // Check if already taken; lapsed registrations past their grace period free the name
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let is_available = is_valid
+        && match USER_PROFILES.may_load(deps.storage, &username)? {
+            Some(profile) => registration_lapsed(&config, &profile, env.block.time),
+            None => true,
+        };
+    let registration_fee = if is_valid { registration_fee(&config, &username)? } else { None };
+    
+    Ok(UsernameAvailableResponse {
+        is_available,
+        registration_fee,
     })
 }
 
-fn query_is_admin(deps: Deps, address: String) -> StdResult<AdminResponse> {
-    let addr = deps.api.addr_validate(&address)?;
-    let is_admin = CONTRACT_ADMINS.may_load(deps.storage, &addr)?.unwrap_or(false);
-    Ok(AdminResponse { is_admin })
+fn query_archived_profiles(
+    deps: Deps,
+    username: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ArchivedProfilesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    
+    let archives = ARCHIVED_PROFILES
+        .prefix(&username)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, archive)| archive))
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    Ok(ArchivedProfilesResponse { archives })
+}
+
+// Helper function to validate username format
+fn validate_username(username: &str) -> Result<(), ContractError> {
+    if username.len() < 3 || username.len() > 30 {
+        return Err(ContractError::UsernameTooShortOrLong {});
+    }
+    
+    // Username can only contain letters, numbers, underscores, and hyphens
+    let re = Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
+    if !re.is_match(username) {
+        return Err(ContractError::InvalidUsername {
+            username: username.to_string(),
+        });
+    }
+    
+    Ok(())
+}
+
+// Helper function to check if an address holds a role (owners hold every role)
+fn has_role(deps: Deps, addr: &Addr, role: Role) -> StdResult<bool> {
+    let roles = ADMIN_ROLES.may_load(deps.storage, addr)?.unwrap_or_default();
+    Ok(roles.contains(&role) || roles.contains(&Role::Owner))
+}
+
+// Helper function to take a returned tip off a goal, reopening it if it drops below target
+fn release_goal_earmark(
+    storage: &mut dyn Storage,
+    username: &str,
+    goal_id: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    if let Some(mut goal) = GOALS.may_load(storage, (username, goal_id))? {
+        goal.raised = goal.raised.saturating_sub(amount);
+        if goal.raised < goal.target {
+            goal.completed_at = None;
+        }
+        GOALS.save(storage, (username, goal_id), &goal)?;
+    }
+    Ok(())
+}
+
+// Helper function to reject senders lacking a role
+fn ensure_role(deps: Deps, addr: &Addr, role: Role) -> Result<(), ContractError> {
+    if !has_role(deps, addr, role)? {
+        return Err(ContractError::MissingRole {
+            role: role.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Helper function to count the addresses holding the owner role
+fn count_owners(deps: Deps) -> StdResult<usize> {
+    let mut owners = 0;
+    for item in ADMIN_ROLES.range(deps.storage, None, None, cosmwasm_std::Order::Ascending) {
+        let (_, roles) = item?;
+        if roles.contains(&Role::Owner) {
+            owners += 1;
+        }
+    }
+    Ok(owners)
+}
+
+// Helper function to apply a role revocation, guarding the last owner
+fn revoke_roles(
+    deps: DepsMut,
+    admin: &Addr,
+    revoked: Vec<Role>,
+    kept: Vec<Role>,
+    changed_by: &Addr,
+) -> Result<Vec<Event>, ContractError> {
+    if revoked.contains(&Role::Owner) {
+        if count_owners(deps.as_ref())? <= 1 {
+            return Err(ContractError::LastOwner {});
+        }
+        
+        // A departing owner's pending handover is no longer valid
+        if let Some(proposal) = PENDING_OWNERSHIP.may_load(deps.storage)? {
+            if proposal.proposer == *admin {
+                PENDING_OWNERSHIP.remove(deps.storage);
+            }
+        }
+    }
+    
+    if kept.is_empty() {
+        ADMIN_ROLES.remove(deps.storage, admin);
+    } else {
+        ADMIN_ROLES.save(deps.storage, admin, &kept)?;
+    }
+    
+    Ok(revoked
+        .iter()
+        .map(|role| role_change_event("revoke", admin, *role, changed_by))
+        .collect())
+}
+
+// Audit event emitted for every role grant or revocation
+fn role_change_event(action: &str, address: &Addr, role: Role, changed_by: &Addr) -> Event {
+    Event::new("role_change")
+        .add_attribute("action", action)
+        .add_attribute("address", address)
+        .add_attribute("role", role.to_string())
+        .add_attribute("changed_by", changed_by)
+}
+
+// Helper function to check if a username currently holds an unexpired badge
+fn is_verified(deps: Deps, env: &Env, username: &str) -> bool {
+    PROFILE_VERIFICATIONS
+        .may_load(deps.storage, username)
+        .ok()
+        .flatten()
+        .map(|v| v.is_active(env.block.time))
+        .unwrap_or(false)
+}
+
+// Helper function to reject actions involving a suspended profile
+fn ensure_not_suspended(deps: Deps, username: &str) -> Result<(), ContractError> {
+    if SUSPENDED_PROFILES.has(deps.storage, username) {
+        return Err(ContractError::ProfileSuspended {
+            username: username.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Helper function to require a non-empty reason on moderation actions
+fn validate_moderation_reason(reason: String) -> Result<String, ContractError> {
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(ContractError::MissingField {
+            field: "reason".to_string(),
+        });
+    }
+    Ok(reason)
+}
+
+// Helper function to record a profile under its owner, making it primary if the owner has none
+fn attach_profile(storage: &mut dyn Storage, owner: &Addr, username: &str) -> StdResult<()> {
+    WALLET_PROFILES.save(storage, (owner, username), &true)?;
+    if !WALLET_TO_USERNAME.has(storage, owner) {
+        WALLET_TO_USERNAME.save(storage, owner, &username.to_string())?;
+    }
+    Ok(())
 }
 
-fn query_is_username_available(deps: Deps, username: String) -> StdResult<UsernameAvailableResponse> {
-    // Check if valid username format
-    let is_valid = match validate_username(&username) {
-        Ok(_) => true,
-        Err(_) => false,
+// Helper function to take a profile away from its owner, promoting another of their profiles
+// to primary. Managers were chosen by that owner, so they go too.
+fn detach_profile(storage: &mut dyn Storage, owner: &Addr, username: &str) -> StdResult<()> {
+    WALLET_PROFILES.remove(storage, (owner, username));
+    if WALLET_TO_USERNAME.may_load(storage, owner)?.as_deref() == Some(username) {
+        let next = WALLET_PROFILES
+            .prefix(owner)
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?;
+        match next {
+            Some(next) => WALLET_TO_USERNAME.save(storage, owner, &next)?,
+            None => WALLET_TO_USERNAME.remove(storage, owner),
+        }
+    }
+    
+    let managers = PROFILE_MANAGERS
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for manager in managers {
+        PROFILE_MANAGERS.remove(storage, (username, &manager));
+    }
+    Ok(())
+}
+
+// Helper function to resolve the sender's primary profile
+fn load_sender_username(deps: Deps, sender: &Addr) -> Result<String, ContractError> {
+    let username = WALLET_TO_USERNAME
+        .may_load(deps.storage, sender)?
+        .ok_or_else(|| ContractError::CustomError {
+            message: "Sender wallet address not registered with a profile".to_string(),
+        })?;
+    
+    // A wallet that gave away the profile NFT no longer acts for the profile
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    if profile_owner(deps, &profile)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(username)
+}
+
+// Helper function to resolve the profile the sender acts as: `username` when given, which must
+// be one of the sender's profiles, otherwise the sender's primary profile
+fn resolve_sender_username(
+    deps: Deps,
+    sender: &Addr,
+    username: Option<String>,
+) -> Result<String, ContractError> {
+    let username = match username {
+        Some(username) => username,
+        None => return load_sender_username(deps, sender),
     };
+    if !WALLET_PROFILES.has(deps.storage, (sender, &username)) {
+        return Err(ContractError::Unauthorized {});
+    }
     
-    // Check if already taken
-    let is_available = is_valid && !USER_PROFILES.has(deps.storage, &username);
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    if profile_owner(deps, &profile)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(username)
+}
+
+// Helper function to resolve the wallet in control of a profile. For NFT-backed usernames the
+// cw721 owner is authoritative, since plain transfers bypass the receive hook; a token escrowed
+// in a listing still belongs to the seller.
+fn profile_owner(deps: Deps, profile: &UserProfile) -> StdResult<Addr> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let nft_contract = match config.profile_nft_contract {
+        Some(nft_contract) if PROFILE_NFTS.has(deps.storage, &profile.username) => nft_contract,
+        _ => return Ok(profile.wallet_address.clone()),
+    };
+    let escrowed = LISTINGS
+        .may_load(deps.storage, &profile.username)?
+        .is_some_and(|listing| listing.nft_escrowed);
+    if escrowed {
+        return Ok(profile.wallet_address.clone());
+    }
     
-    Ok(UsernameAvailableResponse { is_available })
+    let owner: Cw721OwnerOfResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_contract.to_string(),
+        msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
+            token_id: profile_token_id(deps.storage, &profile.username)?,
+            include_expired: None,
+        })?,
+    }))?;
+    deps.api.addr_validate(&owner.owner)
 }
 
-// Helper function to validate username format
-fn validate_username(username: &str) -> Result<(), ContractError> {
-    if username.len() < 3 || username.len() > 30 {
-        return Err(ContractError::UsernameTooShortOrLong {});
+// Helper function to check whether a wallet holds a profile, directly or through its NFT
+fn holds_profile(deps: Deps, wallet: &Addr, username: &str) -> StdResult<bool> {
+    if WALLET_PROFILES.has(deps.storage, (wallet, username)) {
+        return Ok(true);
+    }
+    match USER_PROFILES.may_load(deps.storage, username)? {
+        Some(profile) => Ok(profile_owner(deps, &profile)? == wallet),
+        None => Ok(false),
+    }
+}
+
+// Helper function to check whether a wallet, or any profile it holds, is on a recipient's
+// blocklist. Used where a payment is not made as one particular profile.
+fn is_wallet_blocked(deps: Deps, recipient: &str, wallet: &Addr) -> StdResult<bool> {
+    if is_blocked(deps, recipient, None, wallet)? {
+        return Ok(true);
+    }
+    for username in WALLET_PROFILES
+        .prefix(wallet)
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        if is_blocked(deps, recipient, Some(&username?), wallet)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Helper function to validate a block target before it is used as a key
+fn normalize_block_target(deps: Deps, target: BlockTarget) -> Result<BlockTarget, ContractError> {
+    match target {
+        BlockTarget::Username(username) => {
+            validate_username(&username)?;
+            Ok(BlockTarget::Username(username))
+        }
+        BlockTarget::Address(address) => {
+            let addr = addr_validate(deps.api, &address)?;
+            Ok(BlockTarget::Address(addr.to_string()))
+        }
+    }
+}
+
+// Helper function to check whether a tipper is on a recipient's blocklist
+fn is_blocked(
+    deps: Deps,
+    recipient: &str,
+    from_username: Option<&str>,
+    sender: &Addr,
+) -> StdResult<bool> {
+    if let Some(from_username) = from_username {
+        let by_username = BlockTarget::Username(from_username.to_string()).key();
+        if BLOCKLIST.has(deps.storage, (recipient, &by_username)) {
+            return Ok(true);
+        }
     }
+    let by_address = BlockTarget::Address(sender.to_string()).key();
+    Ok(BLOCKLIST.has(deps.storage, (recipient, &by_address)))
+}
+
+// Helper function to reject messages whose operation is currently paused.
+// Admin, moderation and housekeeping messages are never paused.
+fn ensure_not_paused(deps: Deps, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    let status = PAUSE_STATUS.may_load(deps.storage)?.unwrap_or_default();
+    let paused_operation = match msg {
+        ExecuteMsg::RegisterProfile { .. } if status.registrations => Some("registrations"),
+        ExecuteMsg::UpdateProfile { .. }
+        | ExecuteMsg::SetEncryptionKey { .. }
+        | ExecuteMsg::SetReceiptThreshold { .. }
+        | ExecuteMsg::ReceiveNft(_)
+        | ExecuteMsg::ClaimProfileNft { .. }
+        | ExecuteMsg::SetPrimaryProfile { .. }
+        | ExecuteMsg::AddProfileManager { .. }
+        | ExecuteMsg::RemoveProfileManager { .. }
+        | ExecuteMsg::ListUsername { .. }
+        | ExecuteMsg::AcceptOffer { .. } if status.profile_updates => Some("profile updates"),
+        ExecuteMsg::CreateSubscriptionTier { .. }
+        | ExecuteMsg::CreateGoal { .. }
+        | ExecuteMsg::CreateCampaign { .. } if status.profile_updates => Some("profile updates"),
+        ExecuteMsg::RecordTip { .. }
+        | ExecuteMsg::BatchTip { .. }
+        | ExecuteMsg::Subscribe { .. }
+        | ExecuteMsg::CreateStream { .. }
+        | ExecuteMsg::CreateScheduledTip { .. }
+        | ExecuteMsg::BuyUsername { .. }
+        | ExecuteMsg::Deposit {}
+        | ExecuteMsg::TipOnBehalf { .. }
+        | ExecuteMsg::MakeOffer { .. }
+        | ExecuteMsg::ExecuteDueTips { .. }
+        | ExecuteMsg::Pledge { .. } if status.tips => Some("tips"),
+        _ => None,
+    };
     
-    // Username can only contain letters, numbers, underscores, and hyphens
-    let re = Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
-    if !re.is_match(username) {
-        return Err(ContractError::InvalidUsername {
-            username: username.to_string(),
+    match paused_operation {
+        Some(operation) => Err(ContractError::Paused {
+            operation: operation.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+// Helper function to require exactly the expected coin to be attached
+fn ensure_exact_funds(info: &MessageInfo, expected: &Coin) -> Result<(), ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin == expected => Ok(()),
+        _ => Err(ContractError::InvalidFunds {
+            reason: format!("expected exactly {}", expected),
+        }),
+    }
+}
+
+// Helper function to add a coin to a list, merging amounts of the same denom
+fn add_coin(coins: &mut Vec<Coin>, coin: Coin) {
+    match coins.iter_mut().find(|c| c.denom == coin.denom) {
+        Some(existing) => existing.amount += coin.amount,
+        None => coins.push(coin),
+    }
+}
+
+// Helper function to remove a coin from a list of balances, failing if it is not covered
+fn take_coin(coins: &mut Vec<Coin>, coin: &Coin) -> Result<(), ContractError> {
+    let available = coins
+        .iter()
+        .find(|c| c.denom == coin.denom)
+        .map(|c| c.amount)
+        .unwrap_or_default();
+    if coin.amount.is_zero() || available < coin.amount {
+        return Err(ContractError::InsufficientDeposit {
+            reason: format!("{}{} available", available, coin.denom),
+        });
+    }
+    coins.retain_mut(|c| {
+        if c.denom == coin.denom {
+            c.amount -= coin.amount;
+        }
+        !c.amount.is_zero()
+    });
+    Ok(())
+}
+
+// Helper function to start a new spending period once the current one has ended
+fn roll_allowance_period(allowance: &mut TipAllowance, now: Timestamp) {
+    let elapsed = now.seconds().saturating_sub(allowance.period_start.seconds());
+    if elapsed >= allowance.period_seconds {
+        let periods = elapsed / allowance.period_seconds;
+        allowance.period_start = allowance
+            .period_start
+            .plus_seconds(periods * allowance.period_seconds);
+        allowance.spent.clear();
+    }
+}
+
+// Helper function to persist a tip record along with the sent/received indexes.
+// Several tips between the same pair in one block would share a key, so the
+// timestamp is nudged forward a nanosecond at a time until the key is free.
+fn store_tip(storage: &mut dyn Storage, mut tip: TipRecord) -> StdResult<(String, TipRecord)> {
+    let mut tip_key = generate_tip_key(&tip.from_username, &tip.to_username, tip.timestamp.nanos());
+    while TIP_RECORDS.has(storage, &tip_key) {
+        tip.timestamp = tip.timestamp.plus_nanos(1);
+        tip_key = generate_tip_key(&tip.from_username, &tip.to_username, tip.timestamp.nanos());
+    }
+    
+    TIP_RECORDS.save(storage, &tip_key, &tip)?;
+    
+    // Update tips sent for sender
+    let mut sender_tips = TIPS_SENT.may_load(storage, &tip.from_username)?.unwrap_or_default();
+    sender_tips.push((tip.to_username.clone(), tip_key.clone()));
+    TIPS_SENT.save(storage, &tip.from_username, &sender_tips)?;
+    
+    // Update tips received for recipient
+    let mut recipient_tips = TIPS_RECEIVED.may_load(storage, &tip.to_username)?.unwrap_or_default();
+    recipient_tips.push((tip.from_username.clone(), tip_key.clone()));
+    TIPS_RECEIVED.save(storage, &tip.to_username, &recipient_tips)?;
+    TIPS_RECEIVED_BY_TIME.save(storage, (&tip.to_username, tip.timestamp.nanos(), &tip_key), &())?;
+    
+    Ok((tip_key, tip))
+}
+
+// Helper function to validate funds attached to a tip against its declared amount
+fn tip_funds(info: &MessageInfo, amount: &str) -> Result<Option<Coin>, ContractError> {
+    match info.funds.as_slice() {
+        [] => Ok(None),
+        [coin] if format!("{}{}", coin.amount, coin.denom) == amount && !coin.amount.is_zero() => {
+            Ok(Some(coin.clone()))
+        }
+        _ => Err(ContractError::InvalidFunds {
+            reason: format!("attached funds must equal {}", amount),
+        }),
+    }
+}
+
+// Helper function to close an escrowed tip and record how it was settled
+fn settle_escrowed_tip(
+    storage: &mut dyn Storage,
+    tip_key: &str,
+    status: TipStatus,
+) -> Result<TipRecord, ContractError> {
+    let mut tip = TIP_RECORDS.load(storage, tip_key)?;
+    if tip.status != TipStatus::Pending {
+        return Err(ContractError::TipNotPending {
+            tip_id: tip_key.to_string(),
+        });
+    }
+    
+    tip.status = status;
+    TIP_RECORDS.save(storage, tip_key, &tip)?;
+    ESCROWED_TIPS.remove(storage, tip_key);
+    PENDING_TIPS.remove(storage, (&tip.to_username, tip_key));
+    
+    Ok(tip)
+}
+
+// Helper function to turn tips held for a newly registered username into regular tips.
+// Returns how many were released, the funds owed to the new owner and any referral payouts.
+fn release_unregistered_name_tips(
+    mut deps: DepsMut,
+    now: Timestamp,
+    profile: &UserProfile,
+) -> StdResult<(usize, Vec<Coin>, Vec<BankMsg>)> {
+    let username = profile.username.as_str();
+    let held_tips = UNREGISTERED_NAME_TIPS
+        .prefix(username)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    
+    let mut payouts: Vec<Coin> = Vec::new();
+    let mut referral_msgs = Vec::new();
+    for (tip_id, held) in &held_tips {
+        UNREGISTERED_NAME_TIPS.remove(deps.storage, (username, *tip_id));
+        
+        let (net, referral_msg) = split_tip_funds(deps.branch(), now, profile, held.funds.clone())?;
+        let tip = TipRecord {
+            from_username: held.from_username.clone(),
+            to_username: username.to_string(),
+            amount: format!("{}{}", held.funds.amount, held.funds.denom),
+            message: held.message.clone(),
+            timestamp: held.created_at,
+            moderation: None,
+            goal_id: None,
+            status: TipStatus::Completed,
+            reply: None,
+            encrypted_message: None,
+            visibility: TipVisibility::default(),
+            paid_by: Some(held.sender.clone()),
+            delivered: Some(net.clone()),
+        };
+        store_tip(deps.storage, tip)?;
+        add_coin(&mut payouts, net);
+        referral_msgs.extend(referral_msg);
+    }
+    
+    Ok((held_tips.len(), payouts, referral_msgs))
+}
+
+// Helper function to take the platform fee from tip funds on their way to a recipient.
+// While the recipient's referral is active, part of the fee is paid to their referrer.
+// Returns the recipient's net amount and the referral payout, if any.
+fn split_tip_funds(
+    deps: DepsMut,
+    now: Timestamp,
+    recipient: &UserProfile,
+    funds: Coin,
+) -> StdResult<(Coin, Option<BankMsg>)> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let fee = funds.amount.multiply_ratio(config.platform_fee_bps, 10_000u64);
+    if fee.is_zero() {
+        return Ok((funds, None));
+    }
+    let net = Coin::new((funds.amount - fee).u128(), &funds.denom);
+    
+    let mut kept = fee;
+    let mut referral_msg = None;
+    if let Some(referrer) = &recipient.referrer {
+        let active = now < recipient.created_at.plus_seconds(config.referral_period_seconds);
+        let share = fee.multiply_ratio(config.referral_share_bps, 10_000u64);
+        let referrer_profile = USER_PROFILES.may_load(deps.storage, referrer)?;
+        if let (true, false, Some(referrer_profile)) = (active, share.is_zero(), referrer_profile) {
+            let share = Coin::new(share.u128(), &funds.denom);
+            let mut earnings = REFERRAL_EARNINGS.may_load(deps.storage, referrer)?.unwrap_or_default();
+            add_coin(&mut earnings, share.clone());
+            REFERRAL_EARNINGS.save(deps.storage, referrer, &earnings)?;
+            
+            kept -= share.amount;
+            referral_msg = Some(BankMsg::Send {
+                to_address: profile_owner(deps.as_ref(), &referrer_profile)?.to_string(),
+                amount: vec![share],
+            });
+        }
+    }
+    
+    if !kept.is_zero() {
+        let mut fees = PLATFORM_FEES.may_load(deps.storage)?.unwrap_or_default();
+        add_coin(&mut fees, Coin::new(kept.u128(), &funds.denom));
+        PLATFORM_FEES.save(deps.storage, &fees)?;
+    }
+    
+    Ok((net, referral_msg))
+}
+
+// Helper function to compute what one scheduled tip execution takes from the budget:
+// the tip itself plus the keeper reward
+fn scheduled_tip_cost(amount: &Coin, keeper_reward_bps: u64) -> Uint128 {
+    amount.amount + amount.amount.multiply_ratio(keeper_reward_bps, 10_000u64)
+}
+
+// Helper function to validate an encryption scheme identifier
+fn validate_encryption_scheme(scheme: &str) -> Result<(), ContractError> {
+    let valid = !scheme.is_empty()
+        && scheme.len() <= 64
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(ContractError::InvalidEncryption {
+            reason: "scheme must be 1 to 64 lowercase letters, digits, '-' or '_'".to_string(),
         });
     }
+    Ok(())
+}
+
+// Helper function to check a private tip message against the recipient's published key
+fn validate_encrypted_message(
+    deps: Deps,
+    recipient: &str,
+    encrypted: &EncryptedMessage,
+) -> Result<(), ContractError> {
+    let key = USER_PROFILES
+        .load(deps.storage, recipient)?
+        .encryption_key
+        .ok_or_else(|| ContractError::EncryptionKeyNotPublished {
+            username: recipient.to_string(),
+        })?;
     
+    if encrypted.scheme != key.scheme {
+        return Err(ContractError::InvalidEncryption {
+            reason: format!("message must use the recipient's scheme '{}'", key.scheme),
+        });
+    }
+    if encrypted.ciphertext.is_empty() || encrypted.ciphertext.len() > MAX_ENCRYPTED_MESSAGE_BYTES {
+        return Err(ContractError::InvalidEncryption {
+            reason: format!("ciphertext must be 1 to {} bytes", MAX_ENCRYPTED_MESSAGE_BYTES),
+        });
+    }
     Ok(())
 }
 
-// Helper function to check if an address is an admin
-fn is_admin(deps: Deps, addr: &Addr) -> StdResult<bool> {
-    Ok(CONTRACT_ADMINS.may_load(deps.storage, addr)?.unwrap_or(false))
+// Helper function to reject basis point settings above 100%
+fn validate_bps(field: &str, bps: u64) -> Result<u64, ContractError> {
+    if bps > 10_000 {
+        return Err(ContractError::InvalidBasisPoints {
+            field: field.to_string(),
+        });
+    }
+    Ok(bps)
+}
+
+// Helper function to build the receipt NFT mint for a funded tip that reaches the
+// recipient's threshold. Returns None when receipts are not configured or not earned.
+fn receipt_submsg(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    tip_key: &str,
+    tip: &TipRecord,
+    funds: &Coin,
+) -> StdResult<Option<SubMsg>> {
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let nft_contract = match config.receipt_nft_contract {
+        Some(nft_contract) => nft_contract,
+        None => return Ok(None),
+    };
+    let threshold = match RECEIPT_THRESHOLDS.may_load(deps.storage, &tip.to_username)? {
+        Some(threshold) => threshold,
+        None => return Ok(None),
+    };
+    if funds.denom != threshold.denom || funds.amount < threshold.amount {
+        return Ok(None);
+    }
+    
+    // Supporters with an active subscription get their tier recorded on the receipt
+    let tier = SUBSCRIBER_SUBSCRIPTIONS
+        .prefix(sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|id| SUBSCRIPTIONS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .find(|sub| sub.creator == tip.to_username && sub.is_active(env.block.time))
+        .map(|sub| SUBSCRIPTION_TIERS.load(deps.storage, (&sub.creator, sub.tier_id)))
+        .transpose()?
+        .map(|tier| tier.name);
+    
+    let receipt_number = RECEIPT_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    RECEIPT_SEQ.save(deps.storage, &receipt_number)?;
+    let token_id = format!("receipt-{}", receipt_number);
+    PENDING_RECEIPTS.push_back(deps.storage, &(tip_key.to_string(), token_id.clone()))?;
+    
+    let mut attributes = vec![
+        ("creator", tip.to_username.clone()),
+        ("supporter", tip.from_username.clone()),
+        ("amount", funds.to_string()),
+        ("tipped_at", tip.timestamp.seconds().to_string()),
+    ];
+    if let Some(tier) = tier {
+        attributes.push(("tier", tier));
+    }
+    
+    let mint = Cw721ExecuteMsg::Mint {
+        token_id,
+        owner: sender.to_string(),
+        token_uri: None,
+        extension: NftMetadata {
+            name: Some(format!("Tip receipt #{}", receipt_number)),
+            description: Some(format!("{} tipped {} {}", tip.from_username, tip.to_username, funds)),
+            attributes: Some(
+                attributes
+                    .into_iter()
+                    .map(|(trait_type, value)| NftTrait {
+                        display_type: None,
+                        trait_type: trait_type.to_string(),
+                        value,
+                    })
+                    .collect(),
+            ),
+        },
+    };
+    
+    Ok(Some(SubMsg::reply_always(
+        WasmMsg::Execute {
+            contract_addr: nft_contract.to_string(),
+            msg: to_json_binary(&mint)?,
+            funds: vec![],
+        },
+        RECEIPT_REPLY_ID,
+    )))
+}
+
+// Helper function to build the profile NFT mint for a username, or None when profile
+// NFTs are disabled. The collection must have this contract as its minter.
+fn profile_nft_mint(storage: &mut dyn Storage, profile: &UserProfile) -> StdResult<Option<WasmMsg>> {
+    let config = CONFIG.may_load(storage)?.unwrap_or_default();
+    let nft_contract = match config.profile_nft_contract {
+        Some(nft_contract) => nft_contract,
+        None => return Ok(None),
+    };
+    PROFILE_NFTS.save(storage, &profile.username, &true)?;
+    
+    let mint = Cw721ExecuteMsg::Mint {
+        token_id: profile_token_id(storage, &profile.username)?,
+        owner: profile.wallet_address.to_string(),
+        token_uri: None,
+        extension: NftMetadata {
+            name: Some(profile.username.clone()),
+            description: Some(format!("TipChain profile @{}", profile.username)),
+            attributes: None,
+        },
+    };
+    Ok(Some(WasmMsg::Execute {
+        contract_addr: nft_contract.to_string(),
+        msg: to_json_binary(&mint)?,
+        funds: vec![],
+    }))
+}
+
+// Helper function to hand a profile to a new wallet. Funds held for the username are settled
+// with the previous wallet first and any listing is dropped. Returns the previous wallet and the
+// payouts of the settlement.
+fn move_profile(
+    mut deps: DepsMut,
+    env: &Env,
+    username: &str,
+    new_owner: &Addr,
+) -> Result<(Addr, Vec<BankMsg>), ContractError> {
+    let mut profile = USER_PROFILES.load(deps.storage, username)?;
+    let previous = profile.wallet_address.clone();
+    let msgs = settle_profile_funds(deps.branch(), env, &profile, &previous)?;
+    LISTINGS.remove(deps.storage, username);
+    detach_profile(deps.storage, &previous, username)?;
+    attach_profile(deps.storage, new_owner, username)?;
+    
+    profile.wallet_address = new_owner.clone();
+    profile.updated_at = env.block.time;
+    USER_PROFILES.save(deps.storage, username, &profile)?;
+    
+    Ok((previous, msgs))
+}
+
+// Helper function to record a listing once the seller's authority has been checked
+fn list_username(
+    deps: DepsMut,
+    env: Env,
+    seller: Addr,
+    username: String,
+    price: Coin,
+    keep_profile_data: bool,
+    nft_escrowed: bool,
+) -> Result<Response, ContractError> {
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    if profile.wallet_address != seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if registration_lapsed(&config, &profile, env.block.time) {
+        return Err(ContractError::UsernameExpired { username });
+    }
+    if price.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {
+            amount: price.to_string(),
+        });
+    }
+    
+    let listing = Listing {
+        username: username.clone(),
+        seller,
+        price,
+        keep_profile_data,
+        nft_escrowed,
+        listed_at: env.block.time,
+    };
+    LISTINGS.save(deps.storage, &username, &listing)?;
+    
+    Ok(Response::new()
+        .add_attribute("method", "list_username")
+        .add_attribute("username", username)
+        .add_attribute("price", listing.price.to_string())
+        .add_attribute("keep_profile_data", keep_profile_data.to_string()))
+}
+
+// Helper function to sell a username to an escrowed offer
+fn accept_offer(
+    deps: DepsMut,
+    env: Env,
+    seller: Addr,
+    username: String,
+    offer_id: u64,
+    keep_profile_data: bool,
+    nft_escrowed: bool,
+) -> Result<Response, ContractError> {
+    let profile = USER_PROFILES.load(deps.storage, &username)?;
+    if profile.wallet_address != seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let offer = OFFERS
+        .may_load(deps.storage, (&username, offer_id))?
+        .ok_or(ContractError::OfferNotFound { offer_id })?;
+    OFFERS.remove(deps.storage, (&username, offer_id));
+    
+    let msgs = complete_username_sale(
+        deps,
+        &env,
+        &username,
+        &offer.buyer,
+        offer.price.clone(),
+        keep_profile_data,
+        nft_escrowed,
+    )?;
+    
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "accept_offer")
+        .add_attribute("username", username)
+        .add_attribute("offer_id", offer_id.to_string())
+        .add_attribute("seller", seller)
+        .add_attribute("buyer", offer.buyer)
+        .add_attribute("price", offer.price.to_string()))
+}
+
+// Helper function to hand a sold username to the buyer and pay the seller, keeping the
+// marketplace fee. The buyer's funds must already be held by the contract.
+fn complete_username_sale(
+    mut deps: DepsMut,
+    env: &Env,
+    username: &str,
+    buyer: &Addr,
+    price: Coin,
+    keep_profile_data: bool,
+    nft_escrowed: bool,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    // Suspended profiles cannot change hands, whether bought from a listing or by offer
+    if SUSPENDED_PROFILES.has(deps.storage, username) {
+        return Err(ContractError::ProfileSuspended {
+            username: username.to_string(),
+        });
+    }
+    let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if registration_lapsed(&config, &USER_PROFILES.load(deps.storage, username)?, env.block.time) {
+        return Err(ContractError::UsernameExpired {
+            username: username.to_string(),
+        });
+    }
+    // The NFT decides who holds an NFT-backed username, so it must change hands with the sale
+    if PROFILE_NFTS.has(deps.storage, username) && !nft_escrowed {
+        return Err(ContractError::ProfileNftRequired {
+            username: username.to_string(),
+        });
+    }
+    let (seller, settlement_msgs) = move_profile(deps.branch(), env, username, buyer)?;
+    let storage = deps.storage;
+    
+    // Verification vouches for the previous owner, so it never carries over
+    PROFILE_VERIFICATIONS.remove(storage, username);
+    if !keep_profile_data {
+        let mut profile = USER_PROFILES.load(storage, username)?;
+        profile.name = username.to_string();
+        profile.bio = None;
+        profile.profile_picture = None;
+        profile.banner_image = None;
+        profile.twitter = None;
+        profile.website = None;
+        profile.encryption_key = None;
+        USER_PROFILES.save(storage, username, &profile)?;
+    }
+    
+    let fee = price.amount.multiply_ratio(config.marketplace_fee_bps, 10_000u64);
+    if !fee.is_zero() {
+        let mut fees = PLATFORM_FEES.may_load(storage)?.unwrap_or_default();
+        add_coin(&mut fees, Coin::new(fee.u128(), &price.denom));
+        PLATFORM_FEES.save(storage, &fees)?;
+    }
+    
+    let mut msgs: Vec<CosmosMsg> = vec![BankMsg::Send {
+        to_address: seller.to_string(),
+        amount: vec![Coin::new((price.amount - fee).u128(), &price.denom)],
+    }
+    .into()];
+    msgs.extend(settlement_msgs.into_iter().map(CosmosMsg::from));
+    if nft_escrowed {
+        msgs.push(profile_nft_transfer(storage, username, buyer)?.into());
+    }
+    Ok(msgs)
+}
+
+// Helper function to move a profile NFT held by this contract to `recipient`
+fn profile_nft_transfer(
+    storage: &dyn Storage,
+    username: &str,
+    recipient: &Addr,
+) -> Result<WasmMsg, ContractError> {
+    let config = CONFIG.may_load(storage)?.unwrap_or_default();
+    let nft_contract = config
+        .profile_nft_contract
+        .ok_or(ContractError::ProfileNftsDisabled {})?;
+    let transfer = Cw721ExecuteMsg::TransferNft {
+        recipient: recipient.to_string(),
+        token_id: profile_token_id(storage, username)?,
+    };
+    Ok(WasmMsg::Execute {
+        contract_addr: nft_contract.to_string(),
+        msg: to_json_binary(&transfer)?,
+        funds: vec![],
+    })
+}
+
+// Helper function to price one registration period of a username, if registration is paid
+fn registration_fee(config: &Config, username: &str) -> StdResult<Option<Coin>> {
+    let base = match &config.registration_fee {
+        Some(base) => base,
+        None => return Ok(None),
+    };
+    let shortfall = PREMIUM_NAME_LENGTH.saturating_sub(username.len()) as u32;
+    let amount = base.amount.checked_mul(Uint128::from(1u128 << shortfall))?;
+    Ok(Some(Coin::new(amount.u128(), &base.denom)))
+}
+
+// Helper function to compute when a registration made now lapses
+fn registration_expiry(config: &Config, now: Timestamp) -> Option<Timestamp> {
+    match config.registration_period_seconds {
+        0 => None,
+        period => Some(now.plus_seconds(period)),
+    }
+}
+
+// Helper function to check whether a registration is past its grace period. Registrations
+// never lapse while the period is 0, since they could not be renewed.
+fn registration_lapsed(config: &Config, profile: &UserProfile, now: Timestamp) -> bool {
+    match profile.expires_at {
+        Some(expires_at) if config.registration_period_seconds > 0 => {
+            now > expires_at.plus_seconds(config.registration_grace_seconds)
+        }
+        _ => false,
+    }
+}
+
+// Helper function to get the token id of a username's current profile NFT
+fn profile_token_id(storage: &dyn Storage, username: &str) -> StdResult<String> {
+    match PROFILE_NFT_REISSUES.may_load(storage, username)? {
+        Some(reissues) => Ok(format!("{}#{}", username, reissues)),
+        None => Ok(username.to_string()),
+    }
+}
+
+// What became of a lapsed username's profile NFT when it was archived
+enum ArchivedToken {
+    None,
+    Escrowed,
+    Stale,
+}
+
+// Helper function to archive a lapsed registration so the username can be registered again.
+// Tip history moves to the archived record; settings that belonged to the previous owner are dropped
+// and funds still held for the name are settled. Returns the payouts of that settlement.
+fn archive_lapsed_profile(
+    mut deps: DepsMut,
+    env: &Env,
+    profile: UserProfile,
+    previous_owner: &Addr,
+) -> Result<(ArchivedToken, Vec<BankMsg>), ContractError> {
+    let username = profile.username.clone();
+    detach_profile(deps.storage, &profile.wallet_address, &username)?;
+    let msgs = settle_profile_funds(deps.branch(), env, &profile, previous_owner)?;
+    let storage = deps.storage;
+    
+    let id = ARCHIVE_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    ARCHIVE_SEQ.save(storage, &id)?;
+    let archive = ArchivedProfile {
+        id,
+        profile,
+        archived_at: env.block.time,
+        tips_sent: TIPS_SENT.may_load(storage, &username)?.unwrap_or_default(),
+        tips_received: TIPS_RECEIVED.may_load(storage, &username)?.unwrap_or_default(),
+    };
+    ARCHIVED_PROFILES.save(storage, (&username, id), &archive)?;
+    TIPS_SENT.remove(storage, &username);
+    TIPS_RECEIVED.remove(storage, &username);
+    let indexed = TIPS_RECEIVED_BY_TIME
+        .sub_prefix(&username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (nanos, tip_key) in indexed {
+        TIPS_RECEIVED_BY_TIME.remove(storage, (&username, nanos, &tip_key));
+    }
+    
+    PROFILE_VERIFICATIONS.remove(storage, &username);
+    SUSPENDED_PROFILES.remove(storage, &username);
+    RECEIPT_THRESHOLDS.remove(storage, &username);
+    BLOCK_POLICIES.remove(storage, &username);
+    let pinned = PINNED_TIPS
+        .prefix(&username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for tip_id in pinned {
+        PINNED_TIPS.remove(storage, (&username, &tip_id));
+    }
+    let blocked = BLOCKLIST
+        .prefix(&username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for target in blocked {
+        BLOCKLIST.remove(storage, (&username, &target));
+    }
+    
+    let listing = LISTINGS.may_load(storage, &username)?;
+    LISTINGS.remove(storage, &username);
+    if listing.is_some_and(|listing| listing.nft_escrowed) {
+        return Ok((ArchivedToken::Escrowed, msgs));
+    }
+    if PROFILE_NFTS.has(storage, &username) {
+        // The minter cannot burn a token it does not hold, so the old one is left behind
+        PROFILE_NFTS.remove(storage, &username);
+        PROFILE_NFT_REISSUES.update(storage, &username, |reissues| -> StdResult<u64> {
+            Ok(reissues.unwrap_or_default() + 1)
+        })?;
+        return Ok((ArchivedToken::Stale, msgs));
+    }
+    Ok((ArchivedToken::None, msgs))
+}
+
+// Helper function to settle everything still held for a username that lapses or changes hands,
+// so its next holder never collects funds meant for the previous owner. Earned funds (matured
+// subscription periods, vested streams, succeeded campaigns) go to the previous owner, less the
+// platform fee; the rest goes back to whoever paid it. Unclaimed escrowed tips and the budgets
+// of scheduled tips are returned to their senders.
+fn settle_profile_funds(
+    mut deps: DepsMut,
+    env: &Env,
+    profile: &UserProfile,
+    previous_owner: &Addr,
+) -> Result<Vec<BankMsg>, ContractError> {
+    let storage = &mut *deps.storage;
+    let username = profile.username.as_str();
+    let now = env.block.time;
+    let mut earned: Vec<Coin> = Vec::new();
+    let mut msgs = Vec::new();
+    let refund = |msgs: &mut Vec<BankMsg>, to: &Addr, amount: Uint128, denom: &str| {
+        if !amount.is_zero() {
+            msgs.push(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin::new(amount.u128(), denom)],
+            });
+        }
+    };
+    
+    // Escrowed tips the previous owner never claimed
+    let pending = PENDING_TIPS
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for tip_id in pending {
+        let escrowed = ESCROWED_TIPS.load(storage, &tip_id)?;
+        settle_escrowed_tip(storage, &tip_id, TipStatus::Refunded)?;
+        refund(&mut msgs, &escrowed.sender, escrowed.funds.amount, &escrowed.funds.denom);
+    }
+    let requests = REFUND_REQUESTS
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for tip_id in requests {
+        REFUND_REQUESTS.remove(storage, (username, &tip_id));
+    }
+    
+    // Scheduled tips to the name end and their remaining budget goes back
+    let schedules = SCHEDULED_TIPS_RECEIVED
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for schedule_id in schedules {
+        let mut schedule = SCHEDULED_TIPS.load(storage, schedule_id)?;
+        if schedule.ended_at.is_none() {
+            DUE_SCHEDULED_TIPS.remove(storage, (schedule.next_run_at.seconds(), schedule_id));
+            refund(&mut msgs, &schedule.sender, schedule.budget, &schedule.amount.denom);
+            schedule.budget = Uint128::zero();
+            schedule.ended_at = Some(now);
+            SCHEDULED_TIPS.save(storage, schedule_id, &schedule)?;
+        }
+        SCHEDULED_TIPS_RECEIVED.remove(storage, (username, schedule_id));
+    }
+    
+    // Streams stop vesting now
+    let streams = STREAMS_RECEIVED
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for stream_id in streams {
+        let mut stream = TIP_STREAMS.load(storage, stream_id)?;
+        if stream.cancelled_at.is_none() {
+            let vested = stream.vested(now);
+            refund(&mut msgs, &stream.sender, stream.total - vested, &stream.denom);
+            stream.total = vested;
+            stream.end = now;
+            stream.cancelled_at = Some(now);
+        }
+        add_coin(&mut earned, Coin::new((stream.total - stream.withdrawn).u128(), &stream.denom));
+        stream.withdrawn = stream.total;
+        TIP_STREAMS.save(storage, stream_id, &stream)?;
+        STREAMS_RECEIVED.remove(storage, (username, stream_id));
+    }
+    
+    // Subscriptions end with the periods matured so far; cancelled ones keep the period
+    // the creator already earned at cancellation
+    let subscriptions = CREATOR_SUBSCRIPTIONS
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for subscription_id in subscriptions {
+        let mut subscription = SUBSCRIPTIONS.load(storage, subscription_id)?;
+        if subscription.cancelled_at.is_none() {
+            let matured = subscription.matured_periods(now);
+            let refunded = subscription.price.amount * Uint128::from(subscription.periods_paid - matured);
+            refund(&mut msgs, &subscription.subscriber, refunded, &subscription.price.denom);
+            subscription.periods_paid = matured;
+            subscription.cancelled_at = Some(now);
+        }
+        let unclaimed = subscription.periods_paid - subscription.periods_claimed;
+        add_coin(
+            &mut earned,
+            Coin::new(
+                (subscription.price.amount * Uint128::from(unclaimed)).u128(),
+                &subscription.price.denom,
+            ),
+        );
+        subscription.periods_claimed = subscription.periods_paid;
+        SUBSCRIPTIONS.save(storage, subscription_id, &subscription)?;
+        CREATOR_SUBSCRIPTIONS.remove(storage, (username, subscription_id));
+    }
+    let tiers = SUBSCRIPTION_TIERS
+        .prefix(username)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (tier_id, mut tier) in tiers {
+        tier.active = false;
+        SUBSCRIPTION_TIERS.save(storage, (username, tier_id), &tier)?;
+    }
+    
+    // Open goals close; goals never hold funds
+    let goals = GOALS
+        .prefix(username)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (goal_id, mut goal) in goals {
+        if goal.closed_at.is_none() {
+            goal.closed_at = Some(now);
+            GOALS.save(storage, (username, goal_id), &goal)?;
+        }
+    }
+    
+    // Open campaigns are called off so pledgers can claim refunds; succeeded ones pay out
+    let campaigns = CREATOR_CAMPAIGNS
+        .prefix(username)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for campaign_id in campaigns {
+        let mut campaign = CAMPAIGNS.load(storage, campaign_id)?;
+        match campaign.status(now) {
+            CampaignStatus::Open => campaign.cancelled_at = Some(now),
+            CampaignStatus::Succeeded if !campaign.funds_released => {
+                add_coin(&mut earned, Coin::new(campaign.pledged.u128(), &campaign.denom));
+                campaign.funds_released = true;
+            }
+            _ => {}
+        }
+        CAMPAIGNS.save(storage, campaign_id, &campaign)?;
+        CREATOR_CAMPAIGNS.remove(storage, (username, campaign_id));
+    }
+    
+    let mut payouts: Vec<Coin> = Vec::new();
+    for coin in earned.into_iter().filter(|coin| !coin.amount.is_zero()) {
+        let (net, referral_msg) = split_tip_funds(deps.branch(), now, profile, coin)?;
+        add_coin(&mut payouts, net);
+        msgs.extend(referral_msg);
+    }
+    if !payouts.is_empty() {
+        msgs.push(BankMsg::Send {
+            to_address: previous_owner.to_string(),
+            amount: payouts,
+        });
+    }
+    Ok(msgs)
 }
@@ -9,6 +9,30 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Missing required role: {role}")]
+    MissingRole { role: String },
+
+    #[error("Address '{address}' is not an admin")]
+    AdminNotFound { address: String },
+
+    #[error("Address '{address}' does not hold role: {role}")]
+    RoleNotHeld { address: String, role: String },
+
+    #[error("Cannot remove the last owner")]
+    LastOwner {},
+
+    #[error("Use RenounceAdmin to give up your own roles")]
+    SelfRemoval {},
+
+    #[error("The owner role can only be transferred with ProposeOwnership")]
+    OwnerGrantNotAllowed {},
+
+    #[error("No pending ownership proposal")]
+    NoOwnershipProposal {},
+
+    #[error("Contract is paused for {operation}")]
+    Paused { operation: String },
+
     #[error("Username '{username}' already exists")]
     UsernameExists { username: String },
 
@@ -24,12 +48,166 @@ pub enum ContractError {
     #[error("Cannot tip yourself")]
     SelfTipping {},
 
-    #[error("Wallet address already registered with username '{username}'")]
-    WalletAlreadyRegistered { username: String },
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
 
     #[error("Tip record not found")]
     TipRecordNotFound {},
 
+    #[error("Profile '{username}' is not verified")]
+    VerificationNotFound { username: String },
+
+    #[error("Expiration must be in the future")]
+    InvalidExpiration {},
+
+    #[error("Profile '{username}' is suspended")]
+    ProfileSuspended { username: String },
+
+    #[error("Profile '{username}' is not suspended")]
+    ProfileNotSuspended { username: String },
+
+    #[error("'{username}' does not accept tips from this sender")]
+    SenderBlocked { username: String },
+
+    #[error("Target is not on the blocklist")]
+    NotBlocked {},
+
+    #[error("Invalid funds: {reason}")]
+    InvalidFunds { reason: String },
+
+    #[error("Subscription tier {tier_id} not found")]
+    SubscriptionTierNotFound { tier_id: u64 },
+
+    #[error("Subscription tier {tier_id} is not accepting new subscribers")]
+    SubscriptionTierInactive { tier_id: u64 },
+
+    #[error("Subscription {subscription_id} not found")]
+    SubscriptionNotFound { subscription_id: u64 },
+
+    #[error("Subscription {subscription_id} is already cancelled")]
+    SubscriptionCancelled { subscription_id: u64 },
+
+    #[error("Stream {stream_id} not found")]
+    StreamNotFound { stream_id: u64 },
+
+    #[error("Stream {stream_id} is already cancelled")]
+    StreamCancelled { stream_id: u64 },
+
+    #[error("Scheduled tip {schedule_id} not found")]
+    ScheduledTipNotFound { schedule_id: u64 },
+
+    #[error("Scheduled tip {schedule_id} has already ended")]
+    ScheduledTipEnded { schedule_id: u64 },
+
+    #[error("{field} cannot exceed 10000 basis points")]
+    InvalidBasisPoints { field: String },
+
+    #[error("Invalid amount '{amount}'")]
+    InvalidAmount { amount: String },
+
+    #[error("Goal {goal_id} not found")]
+    GoalNotFound { goal_id: u64 },
+
+    #[error("Goal {goal_id} is no longer accepting tips")]
+    GoalNotActive { goal_id: u64 },
+
+    #[error("Campaign {campaign_id} not found")]
+    CampaignNotFound { campaign_id: u64 },
+
+    #[error("Campaign {campaign_id} is not {expected}")]
+    CampaignWrongStatus { campaign_id: u64, expected: String },
+
+    #[error("Tip '{tip_id}' is not pending")]
+    TipNotPending { tip_id: String },
+
+    #[error("Tip '{tip_id}' cannot be reclaimed before {reclaimable_at}")]
+    TipNotReclaimable { tip_id: String, reclaimable_at: String },
+
+    #[error("'{username}' has not published an encryption key")]
+    EncryptionKeyNotPublished { username: String },
+
+    #[error("Invalid encryption data: {reason}")]
+    InvalidEncryption { reason: String },
+
+    #[error("Reply to tip '{tip_id}' was redacted and can no longer be edited")]
+    ReplyRedacted { tip_id: String },
+
+    #[error("Tip '{tip_id}' has no reply")]
+    ReplyNotFound { tip_id: String },
+
+    #[error("Tip '{tip_id}' was sent as creator-only and cannot be pinned")]
+    TipCreatorOnly { tip_id: String },
+
+    #[error("At most {max} tips can be pinned")]
+    TooManyPinnedTips { max: usize },
+
+    #[error("Insufficient deposited balance: {reason}")]
+    InsufficientDeposit { reason: String },
+
+    #[error("Invalid tip allowance: {reason}")]
+    InvalidTipAllowance { reason: String },
+
+    #[error("No tip allowance granted to '{delegate}'")]
+    TipAllowanceNotFound { delegate: String },
+
+    #[error("Tip allowance exceeded: {reason}")]
+    TipAllowanceExceeded { reason: String },
+
+    #[error("A profile can have at most {max} managers")]
+    TooManyManagers { max: usize },
+
+    #[error("Registration of '{username}' has lapsed")]
+    UsernameExpired { username: String },
+
+    #[error("'{username}' does not need renewing")]
+    RenewalNotRequired { username: String },
+
+    #[error("'{username}' is not listed for sale")]
+    UsernameNotListed { username: String },
+
+    #[error("'{username}' is listed for sale; cancel the listing first")]
+    UsernameListed { username: String },
+
+    #[error("Offer {offer_id} not found")]
+    OfferNotFound { offer_id: u64 },
+
+    #[error("'{username}' is backed by a profile NFT; send the token to list or sell it")]
+    ProfileNftRequired { username: String },
+
+    #[error("Profile NFTs are not enabled")]
+    ProfileNftsDisabled {},
+
+    #[error("'{username}' already has a profile NFT")]
+    ProfileNftExists { username: String },
+
+    #[error("The profile NFT contract cannot change once profile tokens are minted")]
+    ProfileNftContractLocked {},
+
+    #[error("Tip '{tip_id}' cannot be refunded")]
+    TipNotRefundable { tip_id: String },
+
+    #[error("The refund request window for tip '{tip_id}' has closed")]
+    RefundWindowClosed { tip_id: String },
+
+    #[error("A refund was already requested for tip '{tip_id}'")]
+    RefundAlreadyRequested { tip_id: String },
+
+    #[error("Too many tips are already waiting for '{username}'")]
+    TooManyUnregisteredTips { username: String },
+
+    #[error("You already have too many tips waiting for '{username}'")]
+    TooManyUnregisteredTipsFromSender { username: String },
+
+    #[error("Held tip {tip_id} not found")]
+    UnregisteredTipNotFound { tip_id: u64 },
+
+    #[error("Invalid batch: {reason}")]
+    InvalidBatch { reason: String },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
     #[error("Missing or invalid field: {field}")]
     MissingField { field: String },
 
@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    to_json_binary, Addr, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, WasmMsg, WasmQuery,
+    to_json_binary, Addr, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, Uint128, WasmMsg,
+    WasmQuery,
 };
 
 use crate::msg::{ExecuteMsg, QueryMsg, ProfileResponse};
@@ -64,3 +65,14 @@ pub fn parse_tip_key(key: &str) -> Option<(String, String, u64)> {
         None
     }
 }
+
+// Split an amount string such as "5000uxion" into its numeric part and denom
+pub fn parse_amount(amount: &str) -> Option<(Uint128, String)> {
+    let split = amount.find(|c: char| !c.is_ascii_digit())?;
+    let (number, denom) = amount.split_at(split);
+    if number.is_empty() {
+        return None;
+    }
+    let number = number.parse::<u128>().ok()?;
+    Some((Uint128::new(number), denom.to_string()))
+}
@@ -1,71 +1,3452 @@
 #[cfg(test)]
 mod tests {
-    use crate::helpers::CwTemplateContract;
-    use crate::msg::InstantiateMsg;
+    use crate::helpers::TippingContract;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, ProfileResponse, QueryMsg};
+    use crate::ContractError;
     use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+    use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 
     pub fn contract_template() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
-        );
+        )
+        .with_reply(crate::contract::reply);
         Box::new(contract)
     }
 
-    const USER: &str = "USER";
-    const ADMIN: &str = "ADMIN";
-    const NATIVE_DENOM: &str = "denom";
+    const CREATOR: &str = "creator";
+    const FAN_ONE: &str = "fan_one";
+    const FAN_TWO: &str = "fan_two";
+    const ADMIN: &str = "admin";
+    const NATIVE_DENOM: &str = "uxion";
+    const INITIAL_BALANCE: u128 = 1_000_000;
 
     fn mock_app() -> App {
         AppBuilder::new().build(|router, _, storage| {
-            router
-                .bank
-                .init_balance(
-                    storage,
-                    &Addr::unchecked(USER),
-                    vec![Coin {
-                        denom: NATIVE_DENOM.to_string(),
-                        amount: Uint128::new(1),
-                    }],
-                )
-                .unwrap();
+            for user in [CREATOR, FAN_ONE, FAN_TWO] {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(user),
+                        vec![Coin::new(INITIAL_BALANCE, NATIVE_DENOM)],
+                    )
+                    .unwrap();
+            }
         })
     }
 
-    fn proper_instantiate() -> (App, CwTemplateContract) {
+    fn proper_instantiate() -> (App, TippingContract) {
         let mut app = mock_app();
-        let cw_template_id = app.store_code(contract_template());
+        let code_id = app.store_code(contract_template());
+
+        let msg = InstantiateMsg {
+            admin: ADMIN.to_string(),
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "tipchain", None)
+            .unwrap();
+
+        (app, TippingContract(contract_addr))
+    }
+
+    fn register(app: &mut App, contract: &TippingContract, wallet: &str, username: &str) {
+        let msg = ExecuteMsg::RegisterProfile {
+            username: username.to_string(),
+            name: username.to_string(),
+            bio: None,
+            profile_picture: None,
+            banner_image: None,
+            twitter: None,
+            website: None,
+            referrer: None,
+        };
+        app.execute_contract(Addr::unchecked(wallet), contract.addr(), &msg, &[])
+            .unwrap();
+    }
+
+    fn balance(app: &App, address: &str) -> u128 {
+        app.wrap()
+            .query_balance(address, NATIVE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn advance_seconds(app: &mut App, seconds: u64) {
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += seconds / 5;
+        });
+    }
+
+    // Runs a message as `wallet`, surfacing the contract's own error
+    fn execute(
+        app: &mut App,
+        contract: &TippingContract,
+        wallet: &str,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> Result<AppResponse, ContractError> {
+        app.execute_contract(Addr::unchecked(wallet), contract.addr(), msg, funds)
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    // A record-only tip; tests switch on escrow or privacy by editing the returned message
+    fn tip_msg(to_username: &str, amount: u128, message: Option<&str>) -> ExecuteMsg {
+        ExecuteMsg::RecordTip {
+            to_username: to_username.to_string(),
+            amount: format!("{}{}", amount, NATIVE_DENOM),
+            message: message.map(str::to_string),
+            goal_id: None,
+            escrow: None,
+            encrypted_message: None,
+            creator_only: None,
+        }
+    }
+
+    fn profile(app: &App, contract: &TippingContract, username: &str) -> ProfileResponse {
+        app.wrap()
+            .query_wasm_smart(
+                contract.addr(),
+                &QueryMsg::GetProfile {
+                    username: username.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    mod campaign {
+        use super::*;
+        use crate::msg::{CampaignResponse, PledgeResponse, QueryMsg};
+        use crate::state::CampaignStatus;
+
+        const DURATION: u64 = 7 * 24 * 60 * 60;
+
+        // Registers the creator and opens a campaign for 500 uxion
+        fn setup_campaign() -> (App, TippingContract, u64) {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            let deadline = app.block_info().time.plus_seconds(DURATION);
+            let msg = ExecuteMsg::CreateCampaign {
+                description: "New camera".to_string(),
+                target: Coin::new(500, NATIVE_DENOM),
+                deadline,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &msg, &[])
+                .unwrap();
+
+            (app, contract, 1)
+        }
+
+        fn pledge(app: &mut App, contract: &TippingContract, pledger: &str, campaign_id: u64, amount: u128) {
+            let msg = ExecuteMsg::Pledge { campaign_id };
+            app.execute_contract(
+                Addr::unchecked(pledger),
+                contract.addr(),
+                &msg,
+                &[Coin::new(amount, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        fn campaign_status(app: &App, contract: &TippingContract, campaign_id: u64) -> CampaignStatus {
+            let res: CampaignResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::GetCampaign { campaign_id })
+                .unwrap();
+            res.campaign.unwrap().status
+        }
+
+        #[test]
+        fn successful_campaign_releases_funds_to_creator() {
+            let (mut app, contract, campaign_id) = setup_campaign();
+            pledge(&mut app, &contract, FAN_ONE, campaign_id, 300);
+            pledge(&mut app, &contract, FAN_TWO, campaign_id, 250);
+            assert_eq!(balance(&app, contract.addr().as_str()), 550);
+
+            // Funds stay locked while the campaign is open
+            let claim = ExecuteMsg::ClaimCampaignFunds { campaign_id };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+
+            advance_seconds(&mut app, DURATION + 1);
+            assert_eq!(campaign_status(&app, &contract, campaign_id), CampaignStatus::Succeeded);
+
+            // Pledgers cannot pull funds out of a successful campaign
+            let refund = ExecuteMsg::ClaimRefund { campaign_id };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &refund, &[])
+                .unwrap_err();
+
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 550);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+
+            // The release happens only once
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+        }
+
+        #[test]
+        fn failed_campaign_refunds_every_pledger() {
+            let (mut app, contract, campaign_id) = setup_campaign();
+            pledge(&mut app, &contract, FAN_ONE, campaign_id, 100);
+            pledge(&mut app, &contract, FAN_ONE, campaign_id, 50);
+            pledge(&mut app, &contract, FAN_TWO, campaign_id, 200);
+
+            advance_seconds(&mut app, DURATION + 1);
+            assert_eq!(campaign_status(&app, &contract, campaign_id), CampaignStatus::Failed);
+
+            // The creator cannot claim a failed campaign
+            let claim = ExecuteMsg::ClaimCampaignFunds { campaign_id };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+
+            let refund = ExecuteMsg::ClaimRefund { campaign_id };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &refund, &[])
+                .unwrap();
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &refund, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+
+        #[test]
+        fn partial_refund_claims_are_tracked_per_pledger() {
+            let (mut app, contract, campaign_id) = setup_campaign();
+            pledge(&mut app, &contract, FAN_ONE, campaign_id, 120);
+            pledge(&mut app, &contract, FAN_TWO, campaign_id, 80);
+            advance_seconds(&mut app, DURATION + 1);
+
+            // Refunds are not available to someone who never pledged
+            let refund = ExecuteMsg::ClaimRefund { campaign_id };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &refund, &[])
+                .unwrap_err();
+
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &refund, &[])
+                .unwrap();
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert_eq!(balance(&app, contract.addr().as_str()), 80);
+
+            // A second claim by the same pledger is rejected
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &refund, &[])
+                .unwrap_err();
+
+            // The remaining pledger's contribution is untouched
+            let res: PledgeResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetPledge {
+                        campaign_id,
+                        pledger: FAN_TWO.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.amount, Uint128::new(80));
+
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &refund, &[])
+                .unwrap();
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+
+        #[test]
+        fn pledges_must_match_campaign_denom_and_window() {
+            let (mut app, contract, campaign_id) = setup_campaign();
+
+            let msg = ExecuteMsg::Pledge { campaign_id };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, &[])
+                .unwrap_err();
+
+            advance_seconds(&mut app, DURATION + 1);
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &msg,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+        }
+    }
+
+    mod escrow {
+        use super::*;
+        use crate::msg::{QueryMsg, TipsResponse};
+        use crate::state::TipStatus;
+
+        fn setup_escrowed_tip(amount: u128) -> (App, TippingContract) {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("{}{}", amount, NATIVE_DENOM),
+                message: Some("thanks".to_string()),
+                goal_id: None,
+                escrow: Some(true),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &msg,
+                &[Coin::new(amount, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            (app, contract)
+        }
+
+        fn received_tips(app: &App, contract: &TippingContract) -> TipsResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsReceived {
+                        username: "creator_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn recipient_claims_escrowed_tips() {
+            let (mut app, contract) = setup_escrowed_tip(100);
+            assert_eq!(balance(&app, contract.addr().as_str()), 100);
+            assert_eq!(received_tips(&app, &contract).tips[0].status, TipStatus::Pending);
+
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: None,
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+            assert_eq!(received_tips(&app, &contract).tips[0].status, TipStatus::Claimed);
+
+            // Nothing is left to claim
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+        }
+
+        #[test]
+        fn sender_reclaims_after_timeout() {
+            let (mut app, contract) = setup_escrowed_tip(100);
+            let tip = received_tips(&app, &contract).tips[0].clone();
+            let tip_id = format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos());
+
+            let reclaim = ExecuteMsg::ReclaimTip {
+                tip_id: tip_id.clone(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap_err();
+
+            advance_seconds(&mut app, 30 * 24 * 60 * 60);
+
+            // Only the sender can reclaim
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &reclaim, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert_eq!(received_tips(&app, &contract).tips[0].status, TipStatus::Reclaimed);
+
+            // A reclaimed tip cannot be claimed by the recipient anymore
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: Some(vec![tip_id]),
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+        }
+    }
+
+    mod unregistered {
+        use super::*;
+        use crate::msg::{QueryMsg, UnregisteredNameTipsResponse};
+
+        fn tip_unregistered(app: &mut App, contract: &TippingContract, amount: u128) {
+            assert!(try_tip_unregistered(app, contract, FAN_ONE, amount));
+        }
+
+        fn try_tip_unregistered(app: &mut App, contract: &TippingContract, sender: &str, amount: u128) -> bool {
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "future_creator".to_string(),
+                amount: format!("{}{}", amount, NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(sender),
+                contract.addr(),
+                &msg,
+                &[Coin::new(amount, NATIVE_DENOM)],
+            )
+            .is_ok()
+        }
+
+        fn held_tips(app: &App, contract: &TippingContract) -> UnregisteredNameTipsResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetUnregisteredNameTips {
+                        username: "future_creator".to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn registration_releases_held_tips() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            tip_unregistered(&mut app, &contract, 40);
+            tip_unregistered(&mut app, &contract, 60);
+            assert_eq!(held_tips(&app, &contract).tips.len(), 2);
+            assert_eq!(balance(&app, contract.addr().as_str()), 100);
+
+            register(&mut app, &contract, CREATOR, "future_creator");
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+            assert!(held_tips(&app, &contract).tips.is_empty());
+        }
+
+        #[test]
+        fn sender_reclaims_after_expiry() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            tip_unregistered(&mut app, &contract, 40);
+
+            let reclaim = ExecuteMsg::ReclaimUnregisteredTip {
+                username: "future_creator".to_string(),
+                tip_id: 1,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap_err();
+
+            advance_seconds(&mut app, 90 * 24 * 60 * 60);
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert!(held_tips(&app, &contract).tips.is_empty());
+        }
+
+        #[test]
+        fn one_sender_cannot_fill_a_name_with_dust() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+            for _ in 0..5 {
+                tip_unregistered(&mut app, &contract, 1);
+            }
+
+            // The sixth held tip from the same wallet is refused, other senders still fit
+            assert!(!try_tip_unregistered(&mut app, &contract, FAN_ONE, 1));
+            assert!(try_tip_unregistered(&mut app, &contract, FAN_TWO, 50));
+            assert_eq!(held_tips(&app, &contract).tips.len(), 6);
+        }
+    }
+
+    mod batch {
+        use super::*;
+        use crate::msg::BatchTipEntry;
+        use crate::ContractError;
+
+        fn entry(to_username: &str, amount: u128) -> BatchTipEntry {
+            BatchTipEntry {
+                to_username: to_username.to_string(),
+                amount: format!("{}{}", amount, NATIVE_DENOM),
+                message: None,
+            }
+        }
+
+        #[test]
+        fn batch_forwards_each_portion() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let msg = ExecuteMsg::BatchTip {
+                tips: vec![entry("creator_name", 30), entry("fan_two_name", 70)],
+                username: None,
+            };
+
+            // Attached funds must equal the sum of the batch
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(FAN_ONE),
+                    contract.addr(),
+                    &msg,
+                    &[Coin::new(90, NATIVE_DENOM)],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidFunds { .. }
+            ));
+
+            // A batch without funds is rejected rather than recorded as unpaid tips
+            let err = app
+                .execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, &[])
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidFunds { .. }
+            ));
+
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &msg,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 30);
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE + 70);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 100);
+        }
+
+        #[test]
+        fn invalid_recipient_fails_whole_batch() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let msg = ExecuteMsg::BatchTip {
+                tips: vec![entry("creator_name", 30), entry("nobody", 70)],
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &msg,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+        }
+    }
+
+    mod scheduled {
+        use super::*;
+
+        const KEEPER: &str = "keeper";
+        const WEEK: u64 = 7 * 24 * 60 * 60;
+
+        fn schedule(app: &mut App, contract: &TippingContract, budget: u128) {
+            register(app, contract, CREATOR, "creator_name");
+            register(app, contract, FAN_ONE, "fan_one_name");
 
-        let msg = InstantiateMsg { count: 1i32 };
-        let cw_template_contract_addr = app
-            .instantiate_contract(
-                cw_template_id,
-                Addr::unchecked(ADMIN),
+            let msg = ExecuteMsg::CreateScheduledTip {
+                to_username: "creator_name".to_string(),
+                amount: Coin::new(1_000, NATIVE_DENOM),
+                interval_seconds: WEEK,
+                start_at: None,
+                message: Some("weekly".to_string()),
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
                 &msg,
-                &[],
-                "test",
-                None,
+                &[Coin::new(budget, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        fn run_keeper(app: &mut App, contract: &TippingContract) {
+            let msg = ExecuteMsg::ExecuteDueTips { limit: None };
+            app.execute_contract(Addr::unchecked(KEEPER), contract.addr(), &msg, &[])
+                .unwrap();
+        }
+
+        #[test]
+        fn keeper_executes_until_budget_runs_out() {
+            let (mut app, contract) = proper_instantiate();
+            // Two executions of 1000 plus a 0.5% keeper reward each, and some change
+            schedule(&mut app, &contract, 2_500);
+
+            run_keeper(&mut app, &contract);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 1_000);
+            assert_eq!(balance(&app, KEEPER), 5);
+
+            // Not due again until a week has passed
+            run_keeper(&mut app, &contract);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 1_000);
+
+            advance_seconds(&mut app, WEEK);
+            run_keeper(&mut app, &contract);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 2_000);
+            assert_eq!(balance(&app, KEEPER), 10);
+
+            // The leftover budget went back to the fan when the schedule ended
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 2_010);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+
+        #[test]
+        fn sender_cancels_and_withdraws_budget() {
+            let (mut app, contract) = proper_instantiate();
+            schedule(&mut app, &contract, 2_500);
+            run_keeper(&mut app, &contract);
+
+            let cancel = ExecuteMsg::CancelScheduledTip { schedule_id: 1 };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &cancel, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &cancel, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 1_005);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+
+            // Cancelled schedules are no longer executed
+            advance_seconds(&mut app, WEEK);
+            run_keeper(&mut app, &contract);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 1_000);
+        }
+    }
+
+    mod refund {
+        use super::*;
+        use crate::msg::{ConfigUpdate, QueryMsg, TipsResponse};
+        use crate::state::TipStatus;
+
+        fn tip(app: &mut App, contract: &TippingContract, escrow: bool) -> String {
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("100{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: Some(escrow),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &msg,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsSent {
+                        username: "fan_one_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let tip = tips.tips.first().unwrap();
+            format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos())
+        }
+
+        fn status(app: &App, contract: &TippingContract, tip_id: &str) -> TipStatus {
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsReceived {
+                        username: "creator_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            tips.tips
+                .into_iter()
+                .find(|tip| {
+                    format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos())
+                        == tip_id
+                })
+                .unwrap()
+                .status
+        }
+
+        fn setup() -> (App, TippingContract) {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            (app, contract)
+        }
+
+        #[test]
+        fn creator_refunds_delivered_tip() {
+            let (mut app, contract) = setup();
+            let tip_id = tip(&mut app, &contract, false);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+
+            let refund = ExecuteMsg::RefundTip { tip_id: tip_id.clone() };
+            // Only the recipient can refund, and must attach the tip amount
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &refund,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &refund, &[])
+                .unwrap_err();
+            app.execute_contract(
+                Addr::unchecked(CREATOR),
+                contract.addr(),
+                &refund,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert_eq!(status(&app, &contract, &tip_id), TipStatus::Refunded);
+        }
+
+        #[test]
+        fn requested_refund_of_escrowed_tip() {
+            let (mut app, contract) = setup();
+            let tip_id = tip(&mut app, &contract, true);
+
+            let request = ExecuteMsg::RequestRefund {
+                tip_id: tip_id.clone(),
+                reason: Some("extra zero".to_string()),
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &request, &[])
+                .unwrap();
+
+            let refund = ExecuteMsg::RefundTip { tip_id: tip_id.clone() };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &refund, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+            assert_eq!(status(&app, &contract, &tip_id), TipStatus::Refunded);
+
+            // The request window closes after a day
+            let late_tip = tip(&mut app, &contract, false);
+            advance_seconds(&mut app, 24 * 60 * 60 + 1);
+            let request = ExecuteMsg::RequestRefund {
+                tip_id: late_tip,
+                reason: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &request, &[])
+                .unwrap_err();
+        }
+
+        #[test]
+        fn refund_returns_what_the_creator_received() {
+            let (mut app, contract) = setup();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                platform_fee_bps: Some(500),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            let tip_id = tip(&mut app, &contract, false);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 95);
+
+            // The platform fee stays with the contract, so the gross amount is refused
+            let refund = ExecuteMsg::RefundTip { tip_id };
+            app.execute_contract(
+                Addr::unchecked(CREATOR),
+                contract.addr(),
+                &refund,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            app.execute_contract(
+                Addr::unchecked(CREATOR),
+                contract.addr(),
+                &refund,
+                &[Coin::new(95, NATIVE_DENOM)],
             )
             .unwrap();
 
-        let cw_template_contract = CwTemplateContract(cw_template_contract_addr);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 5);
+        }
+
+        #[test]
+        fn record_only_tips_are_not_refundable() {
+            let (mut app, contract) = setup();
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("100{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, &[])
+                .unwrap();
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsSent {
+                        username: "fan_one_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let tip = &tips.tips[0];
+            let tip_id = format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos());
 
-        (app, cw_template_contract)
+            // No funds moved, so neither side can turn the tip into a payment
+            let request = ExecuteMsg::RequestRefund {
+                tip_id: tip_id.clone(),
+                reason: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &request, &[])
+                .unwrap_err();
+            let refund = ExecuteMsg::RefundTip { tip_id: tip_id.clone() };
+            app.execute_contract(
+                Addr::unchecked(CREATOR),
+                contract.addr(),
+                &refund,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+            assert_eq!(status(&app, &contract, &tip_id), TipStatus::Completed);
+        }
     }
 
-    mod count {
+    mod referral {
         use super::*;
-        use crate::msg::ExecuteMsg;
+        use crate::msg::{ConfigUpdate, QueryMsg, ReferralsResponse};
+
+        #[test]
+        fn referrer_earns_share_of_platform_fee() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_TWO, "recruiter");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let msg = ExecuteMsg::RegisterProfile {
+                username: "creator_name".to_string(),
+                name: "Creator".to_string(),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: Some("recruiter".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &msg, &[])
+                .unwrap();
+
+            // 10% platform fee, a fifth of which goes to the referrer
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                platform_fee_bps: Some(1_000),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("1000{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &tip,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 900);
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE + 20);
+            assert_eq!(balance(&app, contract.addr().as_str()), 80);
+
+            let referrals: ReferralsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetReferrals {
+                        username: "recruiter".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(referrals.referrals[0].username, "creator_name");
+            assert_eq!(referrals.earnings, vec![Coin::new(20, NATIVE_DENOM)]);
+
+            // Only treasurers withdraw the platform's part
+            let withdraw = ExecuteMsg::WithdrawFees { recipient: None };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &withdraw, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &withdraw, &[])
+                .unwrap();
+            assert_eq!(balance(&app, ADMIN), 80);
+        }
 
         #[test]
-        fn count() {
-            let (mut app, cw_template_contract) = proper_instantiate();
+        fn wallet_cannot_refer_its_own_profiles() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            let msg = ExecuteMsg::RegisterProfile {
+                username: "creator_alt".to_string(),
+                name: "Creator".to_string(),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: Some("creator_name".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &msg, &[])
+                .unwrap_err();
+            register(&mut app, &contract, CREATOR, "creator_third");
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, &[])
+                .unwrap();
+        }
+    }
+
+    mod receipt {
+        use super::*;
+        use crate::msg::{ConfigUpdate, Cw721ExecuteMsg, QueryMsg, TipReceiptResponse, TipsResponse};
+        use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+
+        // Stand-in for cw721-base that accepts every mint
+        fn mock_cw721() -> Box<dyn Contract<Empty>> {
+            fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+                Ok(Response::new())
+            }
+            fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Cw721ExecuteMsg) -> StdResult<Response> {
+                Ok(Response::new())
+            }
+            fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+                Ok(Binary::default())
+            }
+            Box::new(ContractWrapper::new(execute, instantiate, query))
+        }
+
+        fn tip(app: &mut App, contract: &TippingContract, amount: u128, funded: bool, escrow: bool) -> String {
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("{}{}", amount, NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: Some(escrow),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            let funds = if funded { vec![Coin::new(amount, NATIVE_DENOM)] } else { vec![] };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, &funds)
+                .unwrap();
+
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsSent {
+                        username: "fan_one_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let tip = tips.tips.first().unwrap();
+            format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos())
+        }
+
+        fn receipt(app: &App, contract: &TippingContract, tip_id: &str) -> Option<String> {
+            let receipt: TipReceiptResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipReceipt {
+                        tip_id: tip_id.to_string(),
+                    },
+                )
+                .unwrap();
+            receipt.token_id
+        }
+
+        fn setup() -> (App, TippingContract) {
+            let (mut app, contract) = proper_instantiate();
+            let nft_code_id = app.store_code(mock_cw721());
+            let nft = app
+                .instantiate_contract(nft_code_id, Addr::unchecked(ADMIN), &Empty {}, &[], "receipts", None)
+                .unwrap();
+
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                receipt_nft_contract: Some(nft.to_string()),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            let threshold = ExecuteMsg::SetReceiptThreshold {
+                threshold: Some(Coin::new(500, NATIVE_DENOM)),
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &threshold, &[])
+                .unwrap();
+            (app, contract)
+        }
+
+        #[test]
+        fn tips_above_threshold_mint_receipts() {
+            let (mut app, contract) = setup();
+
+            let small = tip(&mut app, &contract, 100, true, false);
+            assert_eq!(receipt(&app, &contract, &small), None);
+            let large = tip(&mut app, &contract, 500, true, false);
+            assert_eq!(receipt(&app, &contract, &large), Some("receipt-1".to_string()));
+
+            // Record-only tips claim an amount without paying it, so they earn no receipt
+            let unpaid = tip(&mut app, &contract, 5_000, false, false);
+            assert_eq!(receipt(&app, &contract, &unpaid), None);
+        }
+
+        #[test]
+        fn escrowed_tips_earn_receipts_when_claimed() {
+            let (mut app, contract) = setup();
+
+            let claimed = tip(&mut app, &contract, 500, true, true);
+            advance_seconds(&mut app, 5);
+            let reclaimed = tip(&mut app, &contract, 600, true, true);
+            assert_eq!(receipt(&app, &contract, &claimed), None);
+            assert_eq!(receipt(&app, &contract, &reclaimed), None);
+
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: Some(vec![claimed.clone()]),
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap();
+            assert_eq!(receipt(&app, &contract, &claimed), Some("receipt-1".to_string()));
+
+            // A reclaimed tip never reached the recipient, so it earns no receipt
+            advance_seconds(&mut app, 30 * 24 * 60 * 60);
+            let reclaim = ExecuteMsg::ReclaimTip {
+                tip_id: reclaimed.clone(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap();
+            assert_eq!(receipt(&app, &contract, &reclaimed), None);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 500);
+        }
+    }
+
+    mod profile_nft {
+        use super::*;
+        use crate::ContractError;
+        use crate::msg::{
+            ConfigUpdate, Cw721OwnerOfResponse, Cw721QueryMsg, Cw721ReceiveMsg, NftMetadata,
+            ProfileNftHookMsg, ProfileResponse, QueryMsg,
+        };
+        use cosmwasm_schema::cw_serde;
+        use cosmwasm_std::{
+            to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+            WasmMsg,
+        };
+        use cw_storage_plus::Map;
+
+        const OWNERS: Map<&str, Addr> = Map::new("owners");
+
+        #[cw_serde]
+        enum MockCw721Msg {
+            Mint {
+                token_id: String,
+                owner: String,
+                token_uri: Option<String>,
+                extension: NftMetadata,
+            },
+            TransferNft {
+                recipient: String,
+                token_id: String,
+            },
+            SendNft {
+                contract: String,
+                token_id: String,
+                msg: Binary,
+            },
+        }
+
+        // Minimal cw721 keeping track of token owners
+        fn mock_cw721() -> Box<dyn Contract<Empty>> {
+            fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+                Ok(Response::new())
+            }
+            fn execute(deps: DepsMut, _: Env, info: MessageInfo, msg: MockCw721Msg) -> StdResult<Response> {
+                let ensure_owner = |deps: &DepsMut, token_id: &str| -> StdResult<()> {
+                    if OWNERS.load(deps.storage, token_id)? != info.sender {
+                        return Err(StdError::generic_err("not the owner"));
+                    }
+                    Ok(())
+                };
+                match msg {
+                    MockCw721Msg::Mint { token_id, owner, .. } => {
+                        OWNERS.save(deps.storage, &token_id, &Addr::unchecked(owner))?;
+                        Ok(Response::new())
+                    }
+                    MockCw721Msg::TransferNft { recipient, token_id } => {
+                        ensure_owner(&deps, &token_id)?;
+                        OWNERS.save(deps.storage, &token_id, &Addr::unchecked(recipient))?;
+                        Ok(Response::new())
+                    }
+                    MockCw721Msg::SendNft { contract, token_id, msg } => {
+                        ensure_owner(&deps, &token_id)?;
+                        OWNERS.save(deps.storage, &token_id, &Addr::unchecked(&contract))?;
+                        let receive = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                            sender: info.sender.to_string(),
+                            token_id,
+                            msg,
+                        });
+                        Ok(Response::new().add_message(WasmMsg::Execute {
+                            contract_addr: contract,
+                            msg: to_json_binary(&receive)?,
+                            funds: vec![],
+                        }))
+                    }
+                }
+            }
+            fn query(deps: Deps, _: Env, msg: Cw721QueryMsg) -> StdResult<Binary> {
+                let Cw721QueryMsg::OwnerOf { token_id, .. } = msg;
+                to_json_binary(&Cw721OwnerOfResponse {
+                    owner: OWNERS.load(deps.storage, &token_id)?.to_string(),
+                })
+            }
+            Box::new(ContractWrapper::new(execute, instantiate, query))
+        }
+
+        fn profile_wallet(app: &App, contract: &TippingContract) -> Addr {
+            let response: ProfileResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetProfile {
+                        username: "creator_name".to_string(),
+                    },
+                )
+                .unwrap();
+            response.profile.unwrap().wallet_address
+        }
+
+        #[test]
+        fn profile_follows_nft_ownership() {
+            let (mut app, contract) = proper_instantiate();
+            let nft_code_id = app.store_code(mock_cw721());
+            let nft = app
+                .instantiate_contract(nft_code_id, Addr::unchecked(ADMIN), &Empty {}, &[], "profiles", None)
+                .unwrap();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                profile_nft_contract: Some(nft.to_string()),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            // Once a token exists the collection cannot be swapped out
+            let swap = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                profile_nft_contract: Some("other_collection".to_string()),
+                ..ConfigUpdate::default()
+            });
+            let err = app
+                .execute_contract(Addr::unchecked(ADMIN), contract.addr(), &swap, &[])
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::ProfileNftContractLocked {}
+            );
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            // Sending the token through the contract hands the profile over immediately
+            let send = MockCw721Msg::SendNft {
+                contract: contract.addr().to_string(),
+                token_id: "creator_name".to_string(),
+                msg: to_json_binary(&ProfileNftHookMsg::TransferProfile {
+                    recipient: FAN_ONE.to_string(),
+                })
+                .unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), nft.clone(), &send, &[])
+                .unwrap();
+            assert_eq!(profile_wallet(&app, &contract), Addr::unchecked(FAN_ONE));
+
+            // After a plain transfer the token holder controls the profile right away
+            let transfer = MockCw721Msg::TransferNft {
+                recipient: FAN_TWO.to_string(),
+                token_id: "creator_name".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), nft, &transfer, &[])
+                .unwrap();
+            let edit = ExecuteMsg::UpdateProfile {
+                username: "creator_name".to_string(),
+                name: Some("Not mine anymore".to_string()),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &edit, &[])
+                .unwrap_err();
+
+            // Tips are paid to the token holder even before the profile is synced
+            register(&mut app, &contract, CREATOR, "creator_two");
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("100{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(CREATOR),
+                contract.addr(),
+                &tip,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE + 100);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+
+            // Syncing with the collection moves the profile record itself
+            let sync = ExecuteMsg::SyncProfileOwner {
+                username: "creator_name".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &sync, &[])
+                .unwrap();
+            assert_eq!(profile_wallet(&app, &contract), Addr::unchecked(FAN_TWO));
+        }
+
+        #[test]
+        fn referral_share_follows_the_referrer_token() {
+            let (mut app, contract) = proper_instantiate();
+            let nft_code_id = app.store_code(mock_cw721());
+            let nft = app
+                .instantiate_contract(nft_code_id, Addr::unchecked(ADMIN), &Empty {}, &[], "profiles", None)
+                .unwrap();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                profile_nft_contract: Some(nft.to_string()),
+                platform_fee_bps: Some(1_000),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+            let referred = ExecuteMsg::RegisterProfile {
+                username: "fan_one_name".to_string(),
+                name: "Fan".to_string(),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: Some("creator_name".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &referred, &[])
+                .unwrap();
+
+            // The referrer's token changes hands without going through the contract
+            let transfer = MockCw721Msg::TransferNft {
+                recipient: FAN_TWO.to_string(),
+                token_id: "creator_name".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), nft, &transfer, &[])
+                .unwrap();
+
+            // 10% fee on 1000, a fifth of which is the referral share
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "fan_one_name".to_string(),
+                amount: format!("1000{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_TWO),
+                contract.addr(),
+                &tip,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE - 1_000 + 20);
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE);
+        }
+
+        #[test]
+        fn lapsed_name_gets_a_fresh_token() {
+            let (mut app, contract) = proper_instantiate();
+            let nft_code_id = app.store_code(mock_cw721());
+            let nft = app
+                .instantiate_contract(nft_code_id, Addr::unchecked(ADMIN), &Empty {}, &[], "profiles", None)
+                .unwrap();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                profile_nft_contract: Some(nft.to_string()),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+            let owner_of = |app: &App, token_id: &str| -> Addr {
+                let response: Cw721OwnerOfResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        nft.clone(),
+                        &Cw721QueryMsg::OwnerOf {
+                            token_id: token_id.to_string(),
+                            include_expired: None,
+                        },
+                    )
+                    .unwrap();
+                Addr::unchecked(response.owner)
+            };
+
+            register(&mut app, &contract, CREATOR, "creator_name");
+            advance_seconds(&mut app, 400 * 24 * 60 * 60);
+            register(&mut app, &contract, FAN_ONE, "creator_name");
+
+            // The old token stays with its holder; the new registrant gets a reissued one
+            assert_eq!(owner_of(&app, "creator_name"), Addr::unchecked(CREATOR));
+            assert_eq!(owner_of(&app, "creator_name#1"), Addr::unchecked(FAN_ONE));
+            assert_eq!(profile_wallet(&app, &contract), Addr::unchecked(FAN_ONE));
+
+            // The stale token no longer moves the profile
+            let send = MockCw721Msg::SendNft {
+                contract: contract.addr().to_string(),
+                token_id: "creator_name".to_string(),
+                msg: to_json_binary(&ProfileNftHookMsg::TransferProfile {
+                    recipient: FAN_TWO.to_string(),
+                })
+                .unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), nft.clone(), &send, &[])
+                .unwrap_err();
+            let edit = ExecuteMsg::UpdateProfile {
+                username: "creator_name".to_string(),
+                name: Some("Second owner".to_string()),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &edit, &[])
+                .unwrap();
+
+            let send = MockCw721Msg::SendNft {
+                contract: contract.addr().to_string(),
+                token_id: "creator_name#1".to_string(),
+                msg: to_json_binary(&ProfileNftHookMsg::TransferProfile {
+                    recipient: FAN_TWO.to_string(),
+                })
+                .unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), nft.clone(), &send, &[])
+                .unwrap();
+            assert_eq!(owner_of(&app, "creator_name#1"), Addr::unchecked(FAN_TWO));
+            assert_eq!(profile_wallet(&app, &contract), Addr::unchecked(FAN_TWO));
+        }
+
+        #[test]
+        fn listed_username_cannot_claim_its_token() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            let list = ExecuteMsg::ListUsername {
+                price: Coin::new(1_000, NATIVE_DENOM),
+                keep_profile_data: false,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &list, &[])
+                .unwrap();
+
+            let nft_code_id = app.store_code(mock_cw721());
+            let nft = app
+                .instantiate_contract(nft_code_id, Addr::unchecked(ADMIN), &Empty {}, &[], "profiles", None)
+                .unwrap();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                profile_nft_contract: Some(nft.to_string()),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            // Otherwise the seller could be paid for the name while keeping the token
+            let claim = ExecuteMsg::ClaimProfileNft { username: None };
+            let err = app
+                .execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::UsernameListed {
+                    username: "creator_name".to_string()
+                }
+            );
+
+            let cancel = ExecuteMsg::CancelListing { username: None };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &cancel, &[])
+                .unwrap();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap();
+            let err = app
+                .execute_contract(Addr::unchecked(CREATOR), contract.addr(), &list, &[])
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::ProfileNftRequired {
+                    username: "creator_name".to_string()
+                }
+            );
+        }
+    }
+
+    mod marketplace {
+        use super::*;
+        use crate::msg::{OffersResponse, ProfileResponse, QueryMsg};
+
+        fn profile(app: &App, contract: &TippingContract) -> crate::state::UserProfile {
+            let response: ProfileResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetProfile {
+                        username: "creator_name".to_string(),
+                    },
+                )
+                .unwrap();
+            response.profile.unwrap()
+        }
+
+        #[test]
+        fn suspended_username_cannot_be_bought() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            let list = ExecuteMsg::ListUsername {
+                price: Coin::new(1_000, NATIVE_DENOM),
+                keep_profile_data: false,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &list, &[])
+                .unwrap();
+
+            let suspend = ExecuteMsg::SuspendProfile {
+                username: "creator_name".to_string(),
+                reason: "impersonation".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &suspend, &[])
+                .unwrap();
+
+            let buy = ExecuteMsg::BuyUsername {
+                username: "creator_name".to_string(),
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &buy,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(profile(&app, &contract).wallet_address, Addr::unchecked(CREATOR));
+
+            let unsuspend = ExecuteMsg::UnsuspendProfile {
+                username: "creator_name".to_string(),
+                reason: "appeal accepted".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &unsuspend, &[])
+                .unwrap();
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &buy,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(profile(&app, &contract).wallet_address, Addr::unchecked(FAN_ONE));
+        }
+
+        #[test]
+        fn username_sold_by_listing_then_by_offer() {
+            let (mut app, contract) = proper_instantiate();
+            let msg = ExecuteMsg::RegisterProfile {
+                username: "creator_name".to_string(),
+                name: "Creator".to_string(),
+                bio: Some("Original bio".to_string()),
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &msg, &[])
+                .unwrap();
+
+            let list = ExecuteMsg::ListUsername {
+                price: Coin::new(1_000, NATIVE_DENOM),
+                keep_profile_data: false,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &list, &[])
+                .unwrap();
+
+            // Paying less than the asking price is rejected
+            let buy = ExecuteMsg::BuyUsername {
+                username: "creator_name".to_string(),
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &buy,
+                &[Coin::new(900, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &buy,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // Default 2.5% fee stays with the contract and the profile is reset for the buyer
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 975);
+            assert_eq!(balance(&app, contract.addr().as_str()), 25);
+            let bought = profile(&app, &contract);
+            assert_eq!(bought.wallet_address, Addr::unchecked(FAN_ONE));
+            assert_eq!(bought.name, "creator_name");
+            assert_eq!(bought.bio, None);
+
+            // A withdrawn offer is refunded in full
+            let offer = ExecuteMsg::MakeOffer {
+                username: "creator_name".to_string(),
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_TWO),
+                contract.addr(),
+                &offer,
+                &[Coin::new(300, NATIVE_DENOM)],
+            )
+            .unwrap();
+            let withdraw = ExecuteMsg::WithdrawOffer {
+                username: "creator_name".to_string(),
+                offer_id: 1,
+            };
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &withdraw, &[])
+                .unwrap();
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE);
+
+            app.execute_contract(
+                Addr::unchecked(FAN_TWO),
+                contract.addr(),
+                &offer,
+                &[Coin::new(400, NATIVE_DENOM)],
+            )
+            .unwrap();
+            let offers: OffersResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetOffers {
+                        username: "creator_name".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(offers.offers.len(), 1);
+
+            let accept = ExecuteMsg::AcceptOffer {
+                offer_id: 2,
+                keep_profile_data: true,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &accept, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &accept, &[])
+                .unwrap();
+
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 1_000 + 390);
+            assert_eq!(balance(&app, contract.addr().as_str()), 35);
+            assert_eq!(profile(&app, &contract).wallet_address, Addr::unchecked(FAN_TWO));
+        }
+
+        #[test]
+        fn seller_funds_are_settled_before_the_handover() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+
+            let stream = ExecuteMsg::CreateStream {
+                to_username: "creator_name".to_string(),
+                duration_seconds: 1_000,
+                message: None,
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_TWO),
+                contract.addr(),
+                &stream,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+            let escrowed = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("200{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: Some(true),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_TWO),
+                contract.addr(),
+                &escrowed,
+                &[Coin::new(200, NATIVE_DENOM)],
+            )
+            .unwrap();
+            advance_seconds(&mut app, 400);
+
+            let list = ExecuteMsg::ListUsername {
+                price: Coin::new(1_000, NATIVE_DENOM),
+                keep_profile_data: false,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &list, &[])
+                .unwrap();
+            let buy = ExecuteMsg::BuyUsername {
+                username: "creator_name".to_string(),
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &buy,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // The seller keeps what vested; the unvested rest and the escrowed tip go back
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 975 + 400);
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE - 400);
+
+            // Nothing is left for the buyer to collect
+            let withdraw = ExecuteMsg::WithdrawStream { stream_id: 1 };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &withdraw, &[])
+                .unwrap_err();
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: None,
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &claim, &[])
+                .unwrap_err();
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 1_000);
+            assert_eq!(balance(&app, contract.addr().as_str()), 25);
+        }
+    }
+
+    mod registration {
+        use super::*;
+        use crate::msg::{
+            ArchivedProfilesResponse, ConfigUpdate, QueryMsg, ScheduledTipResponse, StatsResponse,
+            UsernameAvailableResponse,
+        };
+        use crate::ContractError;
+
+        const YEAR: u64 = 365 * 24 * 60 * 60;
+
+        fn availability(app: &App, contract: &TippingContract, username: &str) -> UsernameAvailableResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::IsUsernameAvailable {
+                        username: username.to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        fn register_paid(
+            app: &mut App,
+            contract: &TippingContract,
+            wallet: &str,
+            username: &str,
+            funds: &[Coin],
+        ) -> bool {
+            let msg = ExecuteMsg::RegisterProfile {
+                username: username.to_string(),
+                name: "Creator".to_string(),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: None,
+            };
+            app.execute_contract(Addr::unchecked(wallet), contract.addr(), &msg, funds)
+                .is_ok()
+        }
+
+        #[test]
+        fn lapsed_username_is_archived_with_its_tips() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                registration_fee: Some(Coin::new(100, NATIVE_DENOM)),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            // Short names double in price per character below seven
+            let short = availability(&app, &contract, "abc");
+            assert_eq!(short.registration_fee, Some(Coin::new(1_600, NATIVE_DENOM)));
+
+            assert!(!register_paid(&mut app, &contract, CREATOR, "creator_name", &[]));
+            assert!(register_paid(&mut app, &contract, CREATOR, "creator_name", &[Coin::new(100, NATIVE_DENOM)]));
+
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("50{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: None,
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &tip,
+                &[Coin::new(50, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // During the grace period the name stays taken and only its owner can renew
+            advance_seconds(&mut app, YEAR + 1);
+            assert!(!availability(&app, &contract, "creator_name").is_available);
+            let renew = ExecuteMsg::RenewUsername {
+                username: "creator_name".to_string(),
+            };
+            let fee = [Coin::new(100, NATIVE_DENOM)];
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &renew, &fee)
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &renew, &fee)
+                .unwrap();
+
+            // Renewal runs from the old expiry; once it and the grace period pass the name is free
+            advance_seconds(&mut app, YEAR + 30 * 24 * 60 * 60 - 1);
+            assert!(!availability(&app, &contract, "creator_name").is_available);
+            advance_seconds(&mut app, 1);
+            assert!(availability(&app, &contract, "creator_name").is_available);
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &renew, &fee)
+                .unwrap_err();
+
+            assert!(register_paid(&mut app, &contract, FAN_TWO, "creator_name", &fee));
+
+            let archived: ArchivedProfilesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetArchivedProfiles {
+                        username: "creator_name".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(archived.archives.len(), 1);
+            assert_eq!(archived.archives[0].profile.wallet_address, Addr::unchecked(CREATOR));
+            assert_eq!(archived.archives[0].tips_received.len(), 1);
+
+            let stats: StatsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetUserStats {
+                        username: "creator_name".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(stats.total_tips_received, 0);
+
+            // The previous owner's wallet is free to register again
+            assert!(register_paid(&mut app, &contract, CREATOR, "creator_reborn", &fee));
+        }
+
+        #[test]
+        fn reregistering_a_lapsed_name_returns_its_escrowed_tips() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                registration_fee: Some(Coin::new(100, NATIVE_DENOM)),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+            let fee = [Coin::new(100, NATIVE_DENOM)];
+            assert!(register_paid(&mut app, &contract, CREATOR, "creator_name", &fee));
+
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("200{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: Some(true),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &tip,
+                &[Coin::new(200, NATIVE_DENOM)],
+            )
+            .unwrap();
+            let tip_id = format!("fan_one_name:creator_name:{}", app.block_info().time.nanos());
+            let schedule = ExecuteMsg::CreateScheduledTip {
+                to_username: "creator_name".to_string(),
+                amount: Coin::new(100, NATIVE_DENOM),
+                interval_seconds: YEAR,
+                start_at: Some(app.block_info().time.plus_seconds(2 * YEAR)),
+                message: None,
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &schedule,
+                &[Coin::new(300, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 500);
+
+            // The tip is still unclaimed and the schedule unstarted when the name lapses
+            // and someone else takes it
+            advance_seconds(&mut app, YEAR + 30 * 24 * 60 * 60 + 1);
+            assert!(register_paid(&mut app, &contract, FAN_TWO, "creator_name", &fee));
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+            let scheduled: ScheduledTipResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::GetScheduledTip { schedule_id: 1 })
+                .unwrap();
+            assert!(scheduled.schedule.unwrap().ended_at.is_some());
+
+            // The new owner cannot answer or arrange tips sent to the previous one
+            let reply = ExecuteMsg::ReplyToTip {
+                tip_id: tip_id.clone(),
+                message: "thanks".to_string(),
+            };
+            let err = app
+                .execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &reply, &[])
+                .unwrap_err();
+            assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+            let display = ExecuteMsg::SetTipDisplay {
+                tip_id,
+                hidden: Some(true),
+                pinned: None,
+            };
+            let err = app
+                .execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &display, &[])
+                .unwrap_err();
+            assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: None,
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &claim, &[])
+                .unwrap_err();
+            assert_eq!(balance(&app, FAN_TWO), INITIAL_BALANCE - 100);
+        }
+
+        #[test]
+        fn registrations_do_not_lapse_while_the_period_is_zero() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                registration_period_seconds: Some(0),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            // The old expiry no longer applies, since the name could not be renewed
+            advance_seconds(&mut app, 2 * YEAR);
+            let renew = ExecuteMsg::RenewUsername {
+                username: "creator_name".to_string(),
+            };
+            let err = app
+                .execute_contract(Addr::unchecked(CREATOR), contract.addr(), &renew, &[])
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::RenewalNotRequired {
+                    username: "creator_name".to_string()
+                }
+            );
+            assert!(!availability(&app, &contract, "creator_name").is_available);
+            assert!(!register_paid(&mut app, &contract, FAN_ONE, "creator_name", &[]));
+        }
+    }
+
+    mod managers {
+        use super::*;
+        use crate::msg::{ProfileResponse, ProfilesResponse, QueryMsg};
+        use crate::state::BlockTarget;
+        use crate::ContractError;
+
+        fn rename(app: &mut App, contract: &TippingContract, wallet: &str, name: &str) -> bool {
+            let msg = ExecuteMsg::UpdateProfile {
+                username: "creator_name".to_string(),
+                name: Some(name.to_string()),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+            };
+            app.execute_contract(Addr::unchecked(wallet), contract.addr(), &msg, &[])
+                .is_ok()
+        }
+
+        #[test]
+        fn wallet_owns_several_profiles_and_delegates_edits() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, CREATOR, "project_page");
+
+            let profiles: ProfilesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetProfilesByWallet {
+                        wallet: CREATOR.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(profiles.profiles.len(), 2);
+            assert_eq!(profiles.profiles[0].username, "creator_name");
+
+            let primary = ExecuteMsg::SetPrimaryProfile {
+                username: "project_page".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &primary, &[])
+                .unwrap();
+            let response: ProfileResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetProfileByWallet {
+                        wallet: CREATOR.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(response.profile.unwrap().username, "project_page");
+
+            // Only wallets the owner adds as managers may edit the profile
+            assert!(!rename(&mut app, &contract, FAN_ONE, "Agency edit"));
+            let add = ExecuteMsg::AddProfileManager {
+                username: "creator_name".to_string(),
+                manager: FAN_ONE.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &add, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &add, &[])
+                .unwrap();
+            assert!(rename(&mut app, &contract, FAN_ONE, "Agency edit"));
+            assert!(!rename(&mut app, &contract, FAN_TWO, "Stranger edit"));
+
+            let remove = ExecuteMsg::RemoveProfileManager {
+                username: "creator_name".to_string(),
+                manager: FAN_ONE.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &remove, &[])
+                .unwrap();
+            assert!(!rename(&mut app, &contract, FAN_ONE, "Late edit"));
+        }
+
+        #[test]
+        fn wallet_claims_tips_for_a_secondary_profile() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, CREATOR, "project_page");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let tip = ExecuteMsg::RecordTip {
+                to_username: "project_page".to_string(),
+                amount: format!("100{}", NATIVE_DENOM),
+                message: None,
+                goal_id: None,
+                escrow: Some(true),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &tip,
+                &[Coin::new(100, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            let claim = |username: Option<&str>| ExecuteMsg::ClaimTips {
+                tip_ids: None,
+                limit: None,
+                username: username.map(str::to_string),
+            };
+            // The primary profile has nothing pending and other wallets cannot name the profile
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim(None), &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &claim(Some("project_page")), &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim(Some("project_page")), &[])
+                .unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+        }
+
+        #[test]
+        fn secondary_profile_is_funded_only_by_others() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, CREATOR, "project_page");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            register(&mut app, &contract, FAN_ONE, "fan_alt");
+
+            let tier = ExecuteMsg::CreateSubscriptionTier {
+                name: "Supporter".to_string(),
+                price: Coin::new(100, NATIVE_DENOM),
+                period_seconds: 1_000,
+                perks: None,
+                username: Some("project_page".to_string()),
+            };
+            let err = app
+                .execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &tier, &[])
+                .unwrap_err();
+            assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &tier, &[])
+                .unwrap();
+            let campaign = ExecuteMsg::CreateCampaign {
+                description: "New project".to_string(),
+                target: Coin::new(1_000, NATIVE_DENOM),
+                deadline: app.block_info().time.plus_seconds(1_000),
+                username: Some("project_page".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &campaign, &[])
+                .unwrap();
+
+            let subscribe = ExecuteMsg::Subscribe {
+                creator: "project_page".to_string(),
+                tier_id: 1,
+                periods: 1,
+            };
+            let pledge = ExecuteMsg::Pledge { campaign_id: 1 };
+            let funds = [Coin::new(100, NATIVE_DENOM)];
+
+            // The owning wallet cannot fund its secondary profile from its primary one
+            for msg in [&subscribe, &pledge] {
+                let err = app
+                    .execute_contract(Addr::unchecked(CREATOR), contract.addr(), msg, &funds)
+                    .unwrap_err();
+                assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::SelfTipping {});
+            }
+
+            // Blocking any of a fan's profiles keeps that wallet out
+            let block = ExecuteMsg::Block {
+                target: BlockTarget::Username("fan_alt".to_string()),
+                username: Some("project_page".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &block, &[])
+                .unwrap();
+            for msg in [&subscribe, &pledge] {
+                let err = app
+                    .execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), msg, &funds)
+                    .unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap(),
+                    ContractError::SenderBlocked {
+                        username: "project_page".to_string()
+                    }
+                );
+            }
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &pledge, &funds)
+                .unwrap();
+        }
+    }
+
+    mod delegated {
+        use super::*;
+        use crate::msg::{DepositsResponse, QueryMsg, StatsResponse};
+
+        const BOT: &str = "bot";
+
+        fn tip_on_behalf(app: &mut App, contract: &TippingContract, to_username: &str, amount: u128) -> bool {
+            let msg = ExecuteMsg::TipOnBehalf {
+                from_username: "fan_one_name".to_string(),
+                to_username: to_username.to_string(),
+                amount: Coin::new(amount, NATIVE_DENOM),
+                message: Some("Auto-tip".to_string()),
+            };
+            app.execute_contract(Addr::unchecked(BOT), contract.addr(), &msg, &[])
+                .is_ok()
+        }
+
+        #[test]
+        fn delegate_tips_from_deposit_within_allowance() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &ExecuteMsg::Deposit {},
+                &[Coin::new(500, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // Without an allowance the bot cannot spend the deposit
+            assert!(!tip_on_behalf(&mut app, &contract, "creator_name", 60));
+
+            let grant = ExecuteMsg::GrantTipAllowance {
+                delegate: BOT.to_string(),
+                spend_limit: vec![Coin::new(100, NATIVE_DENOM)],
+                period_seconds: 24 * 60 * 60,
+                allowed_recipients: Some(vec!["creator_name".to_string()]),
+                expires_at: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &grant, &[])
+                .unwrap();
+
+            assert!(tip_on_behalf(&mut app, &contract, "creator_name", 60));
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 60);
+
+            // Limits apply per period and only to listed recipients
+            assert!(!tip_on_behalf(&mut app, &contract, "creator_name", 50));
+            assert!(!tip_on_behalf(&mut app, &contract, "fan_two_name", 10));
+            advance_seconds(&mut app, 24 * 60 * 60);
+            assert!(tip_on_behalf(&mut app, &contract, "creator_name", 50));
+
+            let stats: StatsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetUserStats {
+                        username: "fan_one_name".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(stats.total_tips_sent, 2);
+
+            let revoke = ExecuteMsg::RevokeTipAllowance {
+                delegate: BOT.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &revoke, &[])
+                .unwrap();
+            assert!(!tip_on_behalf(&mut app, &contract, "creator_name", 10));
+
+            let deposits: DepositsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetDeposits {
+                        wallet: FAN_ONE.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(deposits.balances, vec![Coin::new(390, NATIVE_DENOM)]);
+
+            let withdraw = ExecuteMsg::WithdrawDeposit {
+                amount: Coin::new(390, NATIVE_DENOM),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &withdraw, &[])
+                .unwrap();
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 110);
+        }
+    }
+
+    mod rbac {
+        use super::*;
+        use crate::msg::{ConfigUpdate, MigrateMsg, QueryMsg, RolesResponse};
+        use crate::state::{Role, ADMIN_ROLES, LEGACY_CONTRACT_ADMINS, LEGACY_VERIFIERS};
+        use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+        const MODERATOR: &str = "moderator";
+
+        fn roles(app: &App, contract: &TippingContract, address: &str) -> Vec<Role> {
+            let res: RolesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetRoles {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.roles
+        }
+
+        #[test]
+        fn each_role_only_unlocks_its_own_actions() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            // Only owners hand out roles, and never the owner role itself
+            let grant = ExecuteMsg::AddAdmin {
+                admin: MODERATOR.to_string(),
+                roles: vec![Role::Moderator],
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &grant, &[])
+                .unwrap_err();
+            let grant_owner = ExecuteMsg::AddAdmin {
+                admin: MODERATOR.to_string(),
+                roles: vec![Role::Owner],
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &grant_owner, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &grant, &[])
+                .unwrap();
+            assert_eq!(roles(&app, &contract, MODERATOR), vec![Role::Moderator]);
+
+            // A moderator can suspend but cannot change config, verify or grant roles
+            let suspend = ExecuteMsg::SuspendProfile {
+                username: "creator_name".to_string(),
+                reason: "spam".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &suspend, &[])
+                .unwrap();
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                platform_fee_bps: Some(100),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &update, &[])
+                .unwrap_err();
+            let verify = ExecuteMsg::VerifyProfile {
+                username: "creator_name".to_string(),
+                category: "creator".to_string(),
+                expires_at: None,
+            };
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &verify, &[])
+                .unwrap_err();
+            let grant_self = ExecuteMsg::AddAdmin {
+                admin: FAN_ONE.to_string(),
+                roles: vec![Role::Moderator],
+            };
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &grant_self, &[])
+                .unwrap_err();
+
+            // Owners hold every role implicitly
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+        }
+
+        #[test]
+        fn verifier_messages_grant_and_revoke_only_the_verifier_role() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            let grant = ExecuteMsg::AddAdmin {
+                admin: MODERATOR.to_string(),
+                roles: vec![Role::Moderator],
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &grant, &[])
+                .unwrap();
+
+            let add = ExecuteMsg::AddVerifier {
+                verifier: MODERATOR.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &add, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &add, &[])
+                .unwrap();
+            assert_eq!(roles(&app, &contract, MODERATOR), vec![Role::Moderator, Role::Verifier]);
+
+            let verify = ExecuteMsg::VerifyProfile {
+                username: "creator_name".to_string(),
+                category: "creator".to_string(),
+                expires_at: None,
+            };
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &verify, &[])
+                .unwrap();
+
+            let remove = ExecuteMsg::RemoveVerifier {
+                verifier: MODERATOR.to_string(),
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &remove, &[])
+                .unwrap();
+            assert_eq!(roles(&app, &contract, MODERATOR), vec![Role::Moderator]);
+            app.execute_contract(Addr::unchecked(MODERATOR), contract.addr(), &verify, &[])
+                .unwrap_err();
+        }
+
+        #[test]
+        fn migrate_moves_legacy_admins_into_roles() {
+            let mut deps = mock_dependencies();
+            let admin = Addr::unchecked(ADMIN);
+            let verifier = Addr::unchecked("verifier");
+            LEGACY_CONTRACT_ADMINS.save(deps.as_mut().storage, &admin, &true).unwrap();
+            LEGACY_VERIFIERS.save(deps.as_mut().storage, &verifier, &true).unwrap();
+
+            crate::contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+            let storage = deps.as_ref().storage;
+            assert_eq!(ADMIN_ROLES.load(storage, &admin).unwrap(), vec![Role::Owner]);
+            assert_eq!(ADMIN_ROLES.load(storage, &verifier).unwrap(), vec![Role::Verifier]);
+            assert!(!LEGACY_CONTRACT_ADMINS.has(storage, &admin));
+            assert!(!LEGACY_VERIFIERS.has(storage, &verifier));
+        }
+    }
+
+    mod goal {
+        use super::*;
+        use crate::msg::{GoalInfo, GoalsResponse, QueryMsg, TipsResponse};
+        use crate::state::GoalStatus;
+
+        const WEEK: u64 = 7 * 24 * 60 * 60;
+
+        fn create_goal(app: &mut App, contract: &TippingContract, target: u128) {
+            let deadline = app.block_info().time.plus_seconds(WEEK);
+            let msg = ExecuteMsg::CreateGoal {
+                description: "New microphone".to_string(),
+                target: Coin::new(target, NATIVE_DENOM),
+                deadline,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &msg, &[])
+                .unwrap();
+        }
+
+        fn tip_towards(
+            app: &mut App,
+            contract: &TippingContract,
+            goal_id: u64,
+            amount: u128,
+            escrow: bool,
+            funds: &[Coin],
+        ) -> bool {
+            advance_seconds(app, 1);
+            let msg = ExecuteMsg::RecordTip {
+                to_username: "creator_name".to_string(),
+                amount: format!("{}{}", amount, NATIVE_DENOM),
+                message: None,
+                goal_id: Some(goal_id),
+                escrow: Some(escrow),
+                encrypted_message: None,
+                creator_only: None,
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &msg, funds)
+                .is_ok()
+        }
+
+        fn goal(app: &App, contract: &TippingContract, goal_id: u64) -> GoalInfo {
+            let res: GoalsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetGoals {
+                        username: "creator_name".to_string(),
+                    },
+                )
+                .unwrap();
+            res.goals.into_iter().find(|info| info.goal.id == goal_id).unwrap()
+        }
+
+        #[test]
+        fn only_funded_tips_count_and_returns_reopen_the_goal() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            create_goal(&mut app, &contract, 100);
+
+            // Record-only tips cannot be earmarked
+            assert!(!tip_towards(&mut app, &contract, 1, 500, false, &[]));
+            assert_eq!(goal(&app, &contract, 1).goal.raised, Uint128::zero());
+
+            assert!(tip_towards(&mut app, &contract, 1, 60, false, &[Coin::new(60, NATIVE_DENOM)]));
+            assert!(tip_towards(&mut app, &contract, 1, 50, true, &[Coin::new(50, NATIVE_DENOM)]));
+            let info = goal(&app, &contract, 1);
+            assert_eq!(info.goal.raised, Uint128::new(110));
+            assert_eq!(info.status, GoalStatus::Completed);
+
+            // A completed goal still stops taking earmarks after its deadline
+            advance_seconds(&mut app, 30 * 24 * 60 * 60);
+            assert!(!tip_towards(&mut app, &contract, 1, 10, false, &[Coin::new(10, NATIVE_DENOM)]));
+
+            // Reclaiming the escrowed tip drops the goal back below its target
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsSent {
+                        username: "fan_one_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let tip = &tips.tips[0];
+            let reclaim = ExecuteMsg::ReclaimTip {
+                tip_id: format!("{}:{}:{}", tip.from_username, tip.to_username, tip.timestamp.nanos()),
+            };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &reclaim, &[])
+                .unwrap();
+            let info = goal(&app, &contract, 1);
+            assert_eq!(info.goal.raised, Uint128::new(60));
+            assert_eq!(info.goal.completed_at, None);
+            assert_eq!(info.status, GoalStatus::Expired);
+        }
+
+        #[test]
+        fn closed_goal_rejects_earmarks() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            create_goal(&mut app, &contract, 100);
+
+            // Only the creator can close their goal
+            let close = ExecuteMsg::CloseGoal { goal_id: 1, username: None };
+            app.execute_contract(Addr::unchecked(FAN_ONE), contract.addr(), &close, &[])
+                .unwrap_err();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &close, &[])
+                .unwrap();
+
+            assert!(!tip_towards(&mut app, &contract, 1, 10, false, &[Coin::new(10, NATIVE_DENOM)]));
+            assert_eq!(goal(&app, &contract, 1).status, GoalStatus::Expired);
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE);
+        }
+    }
+
+    mod verification {
+        use super::*;
+        use crate::msg::ProfilesResponse;
+        use crate::state::Role;
+
+        #[test]
+        fn badge_is_issued_by_verifiers_and_lapses_at_expiry() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let grant = ExecuteMsg::AddAdmin {
+                admin: FAN_TWO.to_string(),
+                roles: vec![Role::Verifier],
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &grant, &[])
+                .unwrap();
+
+            let now = app.block_info().time;
+            let verify = |expires_at| ExecuteMsg::VerifyProfile {
+                username: "creator_name".to_string(),
+                category: "creator".to_string(),
+                expires_at,
+            };
+            let missing_role = ContractError::MissingRole {
+                role: Role::Verifier.to_string(),
+            };
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &verify(None), &[]).unwrap_err(),
+                missing_role
+            );
+            assert_eq!(
+                execute(&mut app, &contract, FAN_TWO, &verify(Some(now)), &[]).unwrap_err(),
+                ContractError::InvalidExpiration {}
+            );
+            execute(&mut app, &contract, FAN_TWO, &verify(Some(now.plus_seconds(100))), &[]).unwrap();
+
+            let response = profile(&app, &contract, "creator_name");
+            assert!(response.is_verified);
+            let verification = response.verification.unwrap();
+            assert_eq!(verification.category, "creator");
+            assert_eq!(verification.verified_by, Addr::unchecked(FAN_TWO));
+
+            let verified: ProfilesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::ListProfiles {
+                        limit: None,
+                        start_after: None,
+                        verified_only: Some(true),
+                    },
+                )
+                .unwrap();
+            assert_eq!(verified.profiles.len(), 1);
+            assert_eq!(verified.profiles[0].username, "creator_name");
+
+            // The badge stays on record but no longer counts once expired
+            advance_seconds(&mut app, 100);
+            let response = profile(&app, &contract, "creator_name");
+            assert!(!response.is_verified);
+            assert!(response.verification.is_some());
+
+            let revoke = ExecuteMsg::RevokeVerification {
+                username: "creator_name".to_string(),
+            };
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &revoke, &[]).unwrap_err(),
+                missing_role
+            );
+            execute(&mut app, &contract, FAN_TWO, &revoke, &[]).unwrap();
+            assert!(profile(&app, &contract, "creator_name").verification.is_none());
+            assert_eq!(
+                execute(&mut app, &contract, FAN_TWO, &revoke, &[]).unwrap_err(),
+                ContractError::VerificationNotFound {
+                    username: "creator_name".to_string()
+                }
+            );
+        }
+    }
+
+    mod moderation {
+        use super::*;
+        use crate::msg::{MigrateMsg, TipDetailResponse};
+        use crate::state::{Role, TipStatus, TIP_RECORDS};
+        use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+        #[test]
+        fn suspended_profile_cannot_receive_tips_until_restored() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let suspend = |reason: &str| ExecuteMsg::SuspendProfile {
+                username: "creator_name".to_string(),
+                reason: reason.to_string(),
+            };
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &suspend("spam"), &[]).unwrap_err(),
+                ContractError::MissingRole {
+                    role: Role::Moderator.to_string()
+                }
+            );
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &suspend(" "), &[]).unwrap_err(),
+                ContractError::MissingField {
+                    field: "reason".to_string()
+                }
+            );
+            execute(&mut app, &contract, ADMIN, &suspend("spam"), &[]).unwrap();
+
+            let suspension = profile(&app, &contract, "creator_name").suspension.unwrap();
+            assert_eq!(suspension.reason, "spam");
+            assert_eq!(suspension.moderated_by, Addr::unchecked(ADMIN));
+            let tip = tip_msg("creator_name", 10, Some("hello"));
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &tip, &[]).unwrap_err(),
+                ContractError::ProfileSuspended {
+                    username: "creator_name".to_string()
+                }
+            );
+
+            let unsuspend = ExecuteMsg::UnsuspendProfile {
+                username: "creator_name".to_string(),
+                reason: "resolved".to_string(),
+            };
+            execute(&mut app, &contract, ADMIN, &unsuspend, &[]).unwrap();
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &unsuspend, &[]).unwrap_err(),
+                ContractError::ProfileNotSuspended {
+                    username: "creator_name".to_string()
+                }
+            );
+            execute(&mut app, &contract, FAN_ONE, &tip, &[]).unwrap();
+        }
+
+        #[test]
+        fn redacted_message_is_removed_but_the_tip_stays() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            let timestamp = app.block_info().time;
+            let tip = tip_msg("creator_name", 10, Some("abusive text"));
+            execute(&mut app, &contract, FAN_ONE, &tip, &[]).unwrap();
+
+            let redact = ExecuteMsg::RedactTipMessage {
+                from_username: "fan_one_name".to_string(),
+                to_username: "creator_name".to_string(),
+                timestamp,
+                reason: "harassment".to_string(),
+            };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &redact, &[]).unwrap_err(),
+                ContractError::MissingRole {
+                    role: Role::Moderator.to_string()
+                }
+            );
+            execute(&mut app, &contract, ADMIN, &redact, &[]).unwrap();
+
+            let detail: TipDetailResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipDetail {
+                        from_username: "fan_one_name".to_string(),
+                        to_username: "creator_name".to_string(),
+                        timestamp,
+                    },
+                )
+                .unwrap();
+            let tip = detail.tip.unwrap();
+            assert_eq!(tip.message, None);
+            assert_eq!(tip.amount, format!("10{}", NATIVE_DENOM));
+            assert_eq!(tip.moderation.unwrap().reason, "harassment");
+        }
+
+        #[test]
+        fn tip_records_written_before_moderation_still_load() {
+            let mut deps = mock_dependencies();
+            let key = TIP_RECORDS.key("fan_one_name:creator_name:1");
+            deps.as_mut().storage.set(
+                &key,
+                br#"{"from_username":"fan_one_name","to_username":"creator_name","amount":"10uxion","message":"hi","timestamp":"1"}"#,
+            );
+
+            crate::contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+            let tip = TIP_RECORDS
+                .load(deps.as_ref().storage, "fan_one_name:creator_name:1")
+                .unwrap();
+            assert_eq!(tip.message, Some("hi".to_string()));
+            assert_eq!(tip.moderation, None);
+            assert_eq!(tip.goal_id, None);
+            assert_eq!(tip.status, TipStatus::Completed);
+        }
+    }
+
+    mod blocklist {
+        use super::*;
+        use crate::msg::{BlockedResponse, TipsResponse};
+        use crate::state::{BlockPolicy, BlockTarget};
+
+        fn latest_message(app: &App, contract: &TippingContract) -> Option<String> {
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsReceived {
+                        username: "creator_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            tips.tips[0].message.clone()
+        }
+
+        #[test]
+        fn blocked_senders_are_rejected_or_silenced_by_policy() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            register(&mut app, &contract, FAN_TWO, "fan_two_name");
+
+            let block = ExecuteMsg::Block {
+                target: BlockTarget::Username("fan_one_name".to_string()),
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &block, &[])
+                .unwrap();
+            let block_wallet = ExecuteMsg::Block {
+                target: BlockTarget::Address(FAN_TWO.to_string()),
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &block_wallet, &[])
+                .unwrap();
+
+            // Blocking by username or wallet rejects tips by default
+            let tip = tip_msg("creator_name", 10, Some("hi"));
+            for wallet in [FAN_ONE, FAN_TWO] {
+                assert_eq!(
+                    execute(&mut app, &contract, wallet, &tip, &[]).unwrap_err(),
+                    ContractError::SenderBlocked {
+                        username: "creator_name".to_string()
+                    }
+                );
+            }
+
+            let policy = ExecuteMsg::SetBlockPolicy {
+                policy: BlockPolicy::DropMessage,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &policy, &[])
+                .unwrap();
+            let blocked = |start_after: Option<String>| -> BlockedResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::GetBlocked {
+                            username: "creator_name".to_string(),
+                            start_after,
+                            limit: Some(1),
+                        },
+                    )
+                    .unwrap()
+            };
+            let first_page = blocked(None);
+            assert_eq!(first_page.policy, BlockPolicy::DropMessage);
+            assert_eq!(first_page.blocked[0].target, BlockTarget::Address(FAN_TWO.to_string()));
+            let second_page = blocked(Some(first_page.blocked[0].target.key()));
+            assert_eq!(
+                second_page.blocked[0].target,
+                BlockTarget::Username("fan_one_name".to_string())
+            );
+            assert!(blocked(Some(second_page.blocked[0].target.key())).blocked.is_empty());
+
+            execute(&mut app, &contract, FAN_ONE, &tip, &[]).unwrap();
+            assert_eq!(latest_message(&app, &contract), None);
+
+            let unblock = ExecuteMsg::Unblock {
+                target: BlockTarget::Username("fan_one_name".to_string()),
+                username: None,
+            };
+            execute(&mut app, &contract, CREATOR, &unblock, &[]).unwrap();
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &unblock, &[]).unwrap_err(),
+                ContractError::NotBlocked {}
+            );
+            advance_seconds(&mut app, 5);
+            execute(&mut app, &contract, FAN_ONE, &tip, &[]).unwrap();
+            assert_eq!(latest_message(&app, &contract), Some("hi".to_string()));
+        }
+    }
+
+    mod admin_removal {
+        use super::*;
+        use crate::msg::{QueryMsg, RolesResponse};
+        use crate::state::Role;
+
+        const MODERATOR: &str = "moderator";
+
+        fn roles(app: &App, contract: &TippingContract, address: &str) -> Vec<Role> {
+            let res: RolesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetRoles {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.roles
+        }
+
+        #[test]
+        fn removal_rejects_self_unknown_admins_and_roles_not_held() {
+            let (mut app, contract) = proper_instantiate();
+            let grant = ExecuteMsg::AddAdmin {
+                admin: MODERATOR.to_string(),
+                roles: vec![Role::Moderator, Role::Verifier],
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &grant, &[])
+                .unwrap();
+
+            let remove = |admin: &str, roles: Option<Vec<Role>>| ExecuteMsg::RemoveAdmin {
+                admin: admin.to_string(),
+                roles,
+            };
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &remove(ADMIN, None), &[]).unwrap_err(),
+                ContractError::SelfRemoval {}
+            );
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &remove(FAN_ONE, None), &[]).unwrap_err(),
+                ContractError::AdminNotFound {
+                    address: FAN_ONE.to_string()
+                }
+            );
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &remove(MODERATOR, Some(vec![Role::Treasurer])), &[])
+                    .unwrap_err(),
+                ContractError::RoleNotHeld {
+                    address: MODERATOR.to_string(),
+                    role: Role::Treasurer.to_string(),
+                }
+            );
+            assert_eq!(
+                execute(&mut app, &contract, MODERATOR, &remove(MODERATOR, Some(vec![Role::Verifier])), &[])
+                    .unwrap_err(),
+                ContractError::MissingRole {
+                    role: Role::Owner.to_string()
+                }
+            );
+
+            execute(&mut app, &contract, ADMIN, &remove(MODERATOR, Some(vec![Role::Verifier])), &[]).unwrap();
+            assert_eq!(roles(&app, &contract, MODERATOR), vec![Role::Moderator]);
+            execute(&mut app, &contract, ADMIN, &remove(MODERATOR, None), &[]).unwrap();
+            assert!(roles(&app, &contract, MODERATOR).is_empty());
+        }
+
+        #[test]
+        fn last_owner_cannot_leave_but_can_hand_over() {
+            let (mut app, contract) = proper_instantiate();
+            let renounce = ExecuteMsg::RenounceAdmin {};
+            assert_eq!(
+                execute(&mut app, &contract, ADMIN, &renounce, &[]).unwrap_err(),
+                ContractError::LastOwner {}
+            );
+            assert_eq!(roles(&app, &contract, ADMIN), vec![Role::Owner]);
+
+            let propose = ExecuteMsg::ProposeOwnership {
+                new_owner: FAN_ONE.to_string(),
+            };
+            execute(&mut app, &contract, ADMIN, &propose, &[]).unwrap();
+            let accept = ExecuteMsg::AcceptOwnership {};
+            assert_eq!(
+                execute(&mut app, &contract, FAN_TWO, &accept, &[]).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            execute(&mut app, &contract, FAN_ONE, &accept, &[]).unwrap();
+
+            assert_eq!(roles(&app, &contract, FAN_ONE), vec![Role::Owner]);
+            assert!(roles(&app, &contract, ADMIN).is_empty());
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &renounce, &[]).unwrap_err(),
+                ContractError::LastOwner {}
+            );
+        }
+    }
+
+    mod pause {
+        use super::*;
+        use crate::msg::{PauseStatusResponse, QueryMsg};
+        use crate::state::Role;
+
+        fn tip(app: &mut App, contract: &TippingContract, escrow: bool) -> Result<AppResponse, ContractError> {
+            let mut msg = tip_msg("creator_name", 100, None);
+            if let ExecuteMsg::RecordTip { escrow: field, .. } = &mut msg {
+                *field = Some(escrow);
+            }
+            execute(app, contract, FAN_ONE, &msg, &[Coin::new(100, NATIVE_DENOM)])
+        }
+
+        fn set_pause(
+            app: &mut App,
+            contract: &TippingContract,
+            sender: &str,
+            paused: bool,
+        ) -> Result<AppResponse, ContractError> {
+            let msg = ExecuteMsg::SetPauseStatus {
+                registrations: Some(paused),
+                profile_updates: Some(paused),
+                tips: Some(paused),
+            };
+            execute(app, contract, sender, &msg, &[])
+        }
+
+        #[test]
+        fn paused_operations_are_rejected_while_funds_can_still_leave() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            tip(&mut app, &contract, true).unwrap();
+
+            assert_eq!(
+                set_pause(&mut app, &contract, FAN_ONE, true).unwrap_err(),
+                ContractError::MissingRole {
+                    role: Role::ConfigAdmin.to_string()
+                }
+            );
+            set_pause(&mut app, &contract, ADMIN, true).unwrap();
+            let status: PauseStatusResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::GetPauseStatus {})
+                .unwrap();
+            assert!(status.any_paused);
+
+            assert_eq!(
+                tip(&mut app, &contract, false).unwrap_err(),
+                ContractError::Paused {
+                    operation: "tips".to_string()
+                }
+            );
+            let register_msg = ExecuteMsg::RegisterProfile {
+                username: "fan_two_name".to_string(),
+                name: "Fan".to_string(),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+                referrer: None,
+            };
+            assert_eq!(
+                execute(&mut app, &contract, FAN_TWO, &register_msg, &[]).unwrap_err(),
+                ContractError::Paused {
+                    operation: "registrations".to_string()
+                }
+            );
+            let update = ExecuteMsg::UpdateProfile {
+                username: "creator_name".to_string(),
+                name: Some("Renamed".to_string()),
+                bio: None,
+                profile_picture: None,
+                banner_image: None,
+                twitter: None,
+                website: None,
+            };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &update, &[]).unwrap_err(),
+                ContractError::Paused {
+                    operation: "profile updates".to_string()
+                }
+            );
+
+            // Claims and moderation keep working during a pause
+            let claim = ExecuteMsg::ClaimTips {
+                tip_ids: None,
+                limit: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &claim, &[])
+                .unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+            let suspend = ExecuteMsg::SuspendProfile {
+                username: "fan_one_name".to_string(),
+                reason: "spam".to_string(),
+            };
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &suspend, &[])
+                .unwrap();
+
+            set_pause(&mut app, &contract, ADMIN, false).unwrap();
+            app.execute_contract(Addr::unchecked(FAN_TWO), contract.addr(), &register_msg, &[])
+                .unwrap();
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &update, &[])
+                .unwrap();
+        }
+    }
+
+    mod subscription {
+        use super::*;
+
+        const PERIOD: u64 = 1_000;
+
+        fn claim(app: &mut App, contract: &TippingContract) -> Result<AppResponse, ContractError> {
+            let msg = ExecuteMsg::ClaimSubscriptions {
+                start_after: None,
+                limit: None,
+                username: None,
+            };
+            execute(app, contract, CREATOR, &msg, &[])
+        }
+
+        #[test]
+        fn creator_claims_matured_periods_and_cancel_refunds_the_rest() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+
+            let tier = ExecuteMsg::CreateSubscriptionTier {
+                name: "Supporter".to_string(),
+                price: Coin::new(100, NATIVE_DENOM),
+                period_seconds: PERIOD,
+                perks: None,
+                username: None,
+            };
+            app.execute_contract(Addr::unchecked(CREATOR), contract.addr(), &tier, &[])
+                .unwrap();
+
+            let subscribe = ExecuteMsg::Subscribe {
+                creator: "creator_name".to_string(),
+                tier_id: 1,
+                periods: 3,
+            };
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &subscribe, &[Coin::new(200, NATIVE_DENOM)])
+                    .unwrap_err(),
+                ContractError::InvalidFunds {
+                    reason: format!("expected exactly 300{}", NATIVE_DENOM)
+                }
+            );
+            execute(&mut app, &contract, FAN_ONE, &subscribe, &[Coin::new(300, NATIVE_DENOM)]).unwrap();
+
+            // Nothing is earned before the first period ends
+            assert_eq!(claim(&mut app, &contract).unwrap_err(), ContractError::NothingToClaim {});
+            advance_seconds(&mut app, PERIOD);
+            claim(&mut app, &contract).unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 100);
+            assert_eq!(claim(&mut app, &contract).unwrap_err(), ContractError::NothingToClaim {});
+
+            // Cancelling mid-period keeps the current period for the creator
+            advance_seconds(&mut app, PERIOD / 2);
+            let cancel = ExecuteMsg::CancelSubscription { subscription_id: 1 };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &cancel, &[]).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            execute(&mut app, &contract, FAN_ONE, &cancel, &[]).unwrap();
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &cancel, &[]).unwrap_err(),
+                ContractError::SubscriptionCancelled { subscription_id: 1 }
+            );
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 200);
+
+            advance_seconds(&mut app, PERIOD);
+            claim(&mut app, &contract).unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 200);
+            assert_eq!(claim(&mut app, &contract).unwrap_err(), ContractError::NothingToClaim {});
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+    }
+
+    mod stream {
+        use super::*;
+        use crate::msg::{ConfigUpdate, PlatformFeesResponse, QueryMsg, StreamResponse};
+
+        fn withdraw(app: &mut App, contract: &TippingContract, wallet: &str) -> Result<AppResponse, ContractError> {
+            execute(app, contract, wallet, &ExecuteMsg::WithdrawStream { stream_id: 1 }, &[])
+        }
+
+        #[test]
+        fn recipient_withdraws_vested_funds_and_cancel_returns_the_rest() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let create = ExecuteMsg::CreateStream {
+                to_username: "creator_name".to_string(),
+                duration_seconds: 1_000,
+                message: None,
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &create,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            advance_seconds(&mut app, 250);
+            let stream: StreamResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::GetStream { stream_id: 1 })
+                .unwrap();
+            assert_eq!(stream.vested, Uint128::new(250));
+            assert_eq!(stream.withdrawable, Uint128::new(250));
+
+            assert_eq!(
+                withdraw(&mut app, &contract, FAN_TWO).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            withdraw(&mut app, &contract, CREATOR).unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 250);
+            assert_eq!(
+                withdraw(&mut app, &contract, CREATOR).unwrap_err(),
+                ContractError::NothingToClaim {}
+            );
+
+            // Cancelling stops vesting; what vested until then stays with the recipient
+            advance_seconds(&mut app, 250);
+            let cancel = ExecuteMsg::CancelStream { stream_id: 1 };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &cancel, &[]).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            execute(&mut app, &contract, FAN_ONE, &cancel, &[]).unwrap();
+            assert_eq!(
+                execute(&mut app, &contract, FAN_ONE, &cancel, &[]).unwrap_err(),
+                ContractError::StreamCancelled { stream_id: 1 }
+            );
+            assert_eq!(balance(&app, FAN_ONE), INITIAL_BALANCE - 500);
+
+            advance_seconds(&mut app, 500);
+            withdraw(&mut app, &contract, CREATOR).unwrap();
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 500);
+            assert_eq!(
+                withdraw(&mut app, &contract, CREATOR).unwrap_err(),
+                ContractError::NothingToClaim {}
+            );
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+
+        #[test]
+        fn withdrawals_pay_the_platform_fee() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            let update = ExecuteMsg::UpdateConfig(ConfigUpdate {
+                platform_fee_bps: Some(1_000),
+                ..ConfigUpdate::default()
+            });
+            app.execute_contract(Addr::unchecked(ADMIN), contract.addr(), &update, &[])
+                .unwrap();
+
+            let create = ExecuteMsg::CreateStream {
+                to_username: "creator_name".to_string(),
+                duration_seconds: 1_000,
+                message: None,
+                username: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(FAN_ONE),
+                contract.addr(),
+                &create,
+                &[Coin::new(1_000, NATIVE_DENOM)],
+            )
+            .unwrap();
+            advance_seconds(&mut app, 1_000);
+            withdraw(&mut app, &contract, CREATOR).unwrap();
+
+            assert_eq!(balance(&app, CREATOR), INITIAL_BALANCE + 900);
+            let fees: PlatformFeesResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::GetPlatformFees {})
+                .unwrap();
+            assert_eq!(fees.fees, vec![Coin::new(100, NATIVE_DENOM)]);
+        }
+    }
+
+    mod reply {
+        use super::*;
+        use crate::msg::{QueryMsg, TipDetailResponse};
+        use crate::state::{Role, TipReply};
+        use cosmwasm_std::Timestamp;
+
+        fn reply(
+            app: &mut App,
+            contract: &TippingContract,
+            wallet: &str,
+            tip_id: &str,
+            message: &str,
+        ) -> Result<AppResponse, ContractError> {
+            let msg = ExecuteMsg::ReplyToTip {
+                tip_id: tip_id.to_string(),
+                message: message.to_string(),
+            };
+            execute(app, contract, wallet, &msg, &[])
+        }
+
+        fn tip_reply(app: &App, contract: &TippingContract, timestamp: Timestamp) -> Option<TipReply> {
+            let detail: TipDetailResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipDetail {
+                        from_username: "fan_one_name".to_string(),
+                        to_username: "creator_name".to_string(),
+                        timestamp,
+                    },
+                )
+                .unwrap();
+            detail.tip.unwrap().reply
+        }
+
+        #[test]
+        fn recipient_replies_edits_and_loses_a_redacted_reply() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let timestamp = app.block_info().time;
+            execute(&mut app, &contract, FAN_ONE, &tip_msg("creator_name", 10, Some("love the work")), &[])
+                .unwrap();
+            let tip_id = format!("fan_one_name:creator_name:{}", timestamp.nanos());
+
+            // Only the recipient replies, and never with an empty message
+            assert_eq!(
+                reply(&mut app, &contract, FAN_ONE, &tip_id, "replying to myself").unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            assert_eq!(
+                reply(&mut app, &contract, CREATOR, &tip_id, "  ").unwrap_err(),
+                ContractError::MissingField {
+                    field: "message".to_string()
+                }
+            );
+            reply(&mut app, &contract, CREATOR, &tip_id, "thank you").unwrap();
+            let first = tip_reply(&app, &contract, timestamp).unwrap();
+            assert_eq!(first.message, Some("thank you".to_string()));
+            assert_eq!(first.edited_at, None);
+
+            // Replying again edits the reply in place
+            advance_seconds(&mut app, 5);
+            reply(&mut app, &contract, CREATOR, &tip_id, "thank you so much").unwrap();
+            let edited = tip_reply(&app, &contract, timestamp).unwrap();
+            assert_eq!(edited.message, Some("thank you so much".to_string()));
+            assert_eq!(edited.replied_at, first.replied_at);
+            assert_eq!(edited.edited_at, Some(app.block_info().time));
+
+            let redact = ExecuteMsg::RedactTipReply {
+                tip_id: tip_id.clone(),
+                reason: "doxxing".to_string(),
+            };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &redact, &[]).unwrap_err(),
+                ContractError::MissingRole {
+                    role: Role::Moderator.to_string()
+                }
+            );
+            execute(&mut app, &contract, ADMIN, &redact, &[]).unwrap();
+            let redacted = tip_reply(&app, &contract, timestamp).unwrap();
+            assert_eq!(redacted.message, None);
+            assert_eq!(redacted.moderation.unwrap().reason, "doxxing");
+            assert_eq!(
+                reply(&mut app, &contract, CREATOR, &tip_id, "trying again").unwrap_err(),
+                ContractError::ReplyRedacted { tip_id }
+            );
+        }
+    }
+
+    mod encryption {
+        use super::*;
+        use crate::msg::{QueryMsg, TipsResponse};
+        use crate::state::{EncryptedMessage, EncryptionKey};
+        use cosmwasm_std::Binary;
+
+        const SCHEME: &str = "x25519-xsalsa20-poly1305";
+
+        fn set_key(
+            app: &mut App,
+            contract: &TippingContract,
+            key: Option<EncryptionKey>,
+        ) -> Result<AppResponse, ContractError> {
+            let msg = ExecuteMsg::SetEncryptionKey {
+                encryption_key: key,
+                username: None,
+            };
+            execute(app, contract, CREATOR, &msg, &[])
+        }
+
+        fn tip(app: &mut App, contract: &TippingContract, scheme: &str) -> Result<AppResponse, ContractError> {
+            let mut msg = tip_msg("creator_name", 10, None);
+            if let ExecuteMsg::RecordTip { encrypted_message, .. } = &mut msg {
+                *encrypted_message = Some(EncryptedMessage {
+                    scheme: scheme.to_string(),
+                    ciphertext: Binary::from(b"sealed".to_vec()),
+                });
+            }
+            execute(app, contract, FAN_ONE, &msg, &[])
+        }
+
+        #[test]
+        fn private_messages_must_match_the_published_key() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let unpublished = ContractError::EncryptionKeyNotPublished {
+                username: "creator_name".to_string(),
+            };
+            assert_eq!(tip(&mut app, &contract, SCHEME).unwrap_err(), unpublished);
+            let key = |scheme: &str, bytes: &[u8]| EncryptionKey {
+                scheme: scheme.to_string(),
+                public_key: Binary::from(bytes.to_vec()),
+            };
+            assert_eq!(
+                set_key(&mut app, &contract, Some(key("X25519!", &[1; 32]))).unwrap_err(),
+                ContractError::InvalidEncryption {
+                    reason: "scheme must be 1 to 64 lowercase letters, digits, '-' or '_'".to_string()
+                }
+            );
+            assert_eq!(
+                set_key(&mut app, &contract, Some(key(SCHEME, &[]))).unwrap_err(),
+                ContractError::InvalidEncryption {
+                    reason: "public key must be 1 to 256 bytes".to_string()
+                }
+            );
+            set_key(&mut app, &contract, Some(key(SCHEME, &[1; 32]))).unwrap();
+
+            let response = profile(&app, &contract, "creator_name");
+            assert_eq!(response.profile.unwrap().encryption_key, Some(key(SCHEME, &[1; 32])));
+
+            assert_eq!(
+                tip(&mut app, &contract, "another-scheme").unwrap_err(),
+                ContractError::InvalidEncryption {
+                    reason: format!("message must use the recipient's scheme '{}'", SCHEME)
+                }
+            );
+            tip(&mut app, &contract, SCHEME).unwrap();
+            let tips: TipsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipsReceived {
+                        username: "creator_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let encrypted = tips.tips[0].encrypted_message.clone().unwrap();
+            assert_eq!(encrypted.ciphertext, Binary::from(b"sealed".to_vec()));
+
+            // Clearing the key stops new private messages
+            set_key(&mut app, &contract, None).unwrap();
+            advance_seconds(&mut app, 5);
+            assert_eq!(tip(&mut app, &contract, SCHEME).unwrap_err(), unpublished);
+        }
+    }
+
+    mod visibility {
+        use super::*;
+        use crate::msg::{QueryMsg, TipWallResponse};
+        use crate::state::TipRecord;
+
+        fn tip(app: &mut App, contract: &TippingContract, message: &str, creator_only: bool) -> String {
+            advance_seconds(app, 5);
+            let mut msg = tip_msg("creator_name", 10, Some(message));
+            if let ExecuteMsg::RecordTip { creator_only: field, .. } = &mut msg {
+                *field = Some(creator_only);
+            }
+            execute(app, contract, FAN_ONE, &msg, &[]).unwrap();
+            format!("fan_one_name:creator_name:{}", app.block_info().time.nanos())
+        }
+
+        fn display(
+            app: &mut App,
+            contract: &TippingContract,
+            wallet: &str,
+            tip_id: &str,
+            hidden: Option<bool>,
+            pinned: Option<bool>,
+        ) -> Result<AppResponse, ContractError> {
+            let msg = ExecuteMsg::SetTipDisplay {
+                tip_id: tip_id.to_string(),
+                hidden,
+                pinned,
+            };
+            execute(app, contract, wallet, &msg, &[])
+        }
+
+        // Messages of the pinned and the other public tips on the wall
+        fn wall(app: &App, contract: &TippingContract) -> (Vec<String>, Vec<String>) {
+            let wall: TipWallResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::GetTipWall {
+                        username: "creator_name".to_string(),
+                        limit: None,
+                        start_after: None,
+                    },
+                )
+                .unwrap();
+            let messages = |tips: Vec<TipRecord>| {
+                tips.into_iter().filter_map(|tip| tip.message).collect::<Vec<_>>()
+            };
+            (messages(wall.pinned), messages(wall.tips))
+        }
+
+        #[test]
+        fn wall_respects_privacy_hiding_and_pins() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+
+            let first = tip(&mut app, &contract, "first", false);
+            let private = tip(&mut app, &contract, "private", true);
+            let last = tip(&mut app, &contract, "last", false);
+            assert_eq!(wall(&app, &contract), (vec![], vec!["last".to_string(), "first".to_string()]));
+
+            // Only the recipient arranges the wall, and creator-only tips cannot be pinned
+            assert_eq!(
+                display(&mut app, &contract, FAN_TWO, &first, None, Some(true)).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            assert_eq!(
+                display(&mut app, &contract, CREATOR, &private, None, Some(true)).unwrap_err(),
+                ContractError::TipCreatorOnly { tip_id: private }
+            );
+            display(&mut app, &contract, CREATOR, &first, None, Some(true)).unwrap();
+            display(&mut app, &contract, CREATOR, &last, Some(true), None).unwrap();
+            assert_eq!(wall(&app, &contract), (vec!["first".to_string()], vec![]));
+
+            // The sender taking a tip off the wall also unpins it
+            let privacy = ExecuteMsg::SetTipPrivacy {
+                tip_id: first.clone(),
+                creator_only: true,
+                username: None,
+            };
+            assert_eq!(
+                execute(&mut app, &contract, CREATOR, &privacy, &[]).unwrap_err(),
+                ContractError::Unauthorized {}
+            );
+            execute(&mut app, &contract, FAN_ONE, &privacy, &[]).unwrap();
+            assert_eq!(wall(&app, &contract), (vec![], vec![]));
+
+            display(&mut app, &contract, CREATOR, &last, Some(false), None).unwrap();
+            assert_eq!(wall(&app, &contract), (vec![], vec!["last".to_string()]));
+        }
+
+        #[test]
+        fn wall_orders_and_pages_by_tip_time() {
+            let (mut app, contract) = proper_instantiate();
+            register(&mut app, &contract, CREATOR, "creator_name");
+            register(&mut app, &contract, FAN_ONE, "fan_one_name");
+            register(&mut app, &contract, FAN_TWO, "alpha_fan");
+
+            // The newer tip comes from the sender whose name sorts first
+            let older = tip(&mut app, &contract, "older", false);
+            advance_seconds(&mut app, 5);
+            execute(&mut app, &contract, FAN_TWO, &tip_msg("creator_name", 10, Some("newer")), &[]).unwrap();
+            let newer = format!("alpha_fan:creator_name:{}", app.block_info().time.nanos());
 
-            let msg = ExecuteMsg::Increment {};
-            let cosmos_msg = cw_template_contract.call(msg).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let page = |start_after: Option<String>| -> Vec<String> {
+                let wall: TipWallResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::GetTipWall {
+                            username: "creator_name".to_string(),
+                            limit: Some(1),
+                            start_after,
+                        },
+                    )
+                    .unwrap();
+                wall.tips.into_iter().filter_map(|tip| tip.message).collect()
+            };
+            assert_eq!(page(None), vec!["newer".to_string()]);
+            assert_eq!(page(Some(newer)), vec!["older".to_string()]);
+            assert!(page(Some(older)).is_empty());
         }
     }
 }
@@ -4,9 +4,6 @@ pub mod helpers;
 pub mod msg;
 pub mod state;
 
-#[cfg(test)]
-mod tests {
-    // We'll include tests in the individual modules
-}
-
 pub use crate::error::ContractError;
+
+mod integration_tests;
@@ -1,13 +1,24 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Timestamp;
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use crate::state::{UserProfile, TipRecord};
+use crate::state::{
+    BlockPolicy, BlockTarget, BlockedEntry, ModerationAction, OwnershipProposal, PauseStatus, Role,
+    Subscription, SubscriptionTier, UserProfile, TipRecord, TipStream, Verification, ScheduledTip,
+    RefundRequest, EncryptionKey, EncryptedMessage, Listing, Offer,
+    ArchivedProfile, TipAllowance,
+    FundraisingGoal, GoalStatus, Campaign, CampaignStatus, Config, UnregisteredNameTip,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: String, // Initial admin address
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     // User profile management
@@ -19,6 +30,7 @@ pub enum ExecuteMsg {
         banner_image: Option<String>,
         twitter: Option<String>,
         website: Option<String>,
+        referrer: Option<String>,
     },
     
     UpdateProfile {
@@ -31,23 +43,490 @@ pub enum ExecuteMsg {
         website: Option<String>,
     },
     
-    // Tip recording functionality
+    // Choose which of the sender's profiles they act as when tipping, listing and the like
+    SetPrimaryProfile {
+        username: String,
+    },
+    
+    // Let another wallet edit a profile through `UpdateProfile`. Managers cannot withdraw,
+    // sell or transfer the profile.
+    AddProfileManager {
+        username: String,
+        manager: String,
+    },
+    
+    // Owners remove managers; managers may also remove themselves
+    RemoveProfileManager {
+        username: String,
+        manager: String,
+    },
+    
+    // Extend the sender's registration by one period, paying the registration fee.
+    // Lapsed names can be renewed by their owner until the grace period ends.
+    RenewUsername {
+        username: String,
+    },
+    
+    // Publish or clear the key fans use to encrypt private tip messages to the sender's profile.
+    // Here and below, `username` picks one of the sender's profiles instead of the primary one.
+    SetEncryptionKey {
+        encryption_key: Option<EncryptionKey>,
+        username: Option<String>,
+    },
+    
+    // Tip recording functionality; `goal_id` earmarks the tip for one of the recipient's goals.
+    // Attached funds must equal `amount` and are forwarded to the recipient, or held by the
+    // contract until claimed when `escrow` is set. Funded tips to a valid username nobody has
+    // registered yet are held and released to whoever registers it. `encrypted_message` must be
+    // encrypted to the recipient's published key. `creator_only` keeps the tip off the public wall.
     RecordTip {
         to_username: String,
         amount: String,
         message: Option<String>,
+        goal_id: Option<u64>,
+        escrow: Option<bool>,
+        encrypted_message: Option<EncryptedMessage>,
+        creator_only: Option<bool>,
+    },
+    
+    // Sender chooses whether a sent tip may appear on the recipient's public wall
+    SetTipPrivacy {
+        tip_id: String,
+        creator_only: bool,
+        username: Option<String>,
+    },
+    
+    // Recipient hides or pins a received tip on their public wall; omitted flags keep their value
+    SetTipDisplay {
+        tip_id: String,
+        hidden: Option<bool>,
+        pinned: Option<bool>,
     },
     
-    // Admin management
+    // Several tips in one transaction; attached funds are required and must equal the per-denom
+    // sum of the amounts, and the whole batch fails if any recipient is invalid
+    BatchTip {
+        tips: Vec<BatchTipEntry>,
+        username: Option<String>,
+    },
+    
+    // Recipient releases escrowed tips; `tip_ids` defaults to the oldest `limit` pending tips
+    ClaimTips {
+        tip_ids: Option<Vec<String>>,
+        limit: Option<u32>,
+        username: Option<String>,
+    },
+    
+    // Sender takes back an unclaimed escrowed tip after the escrow timeout
+    ReclaimTip {
+        tip_id: String,
+    },
+    
+    // Recipient returns a received tip to its sender. Escrowed tips are refunded from escrow;
+    // already delivered tips require the recipient to attach the amount they received.
+    RefundTip {
+        tip_id: String,
+    },
+    
+    // Recipient posts or edits their public reply to a received tip
+    ReplyToTip {
+        tip_id: String,
+        message: String,
+    },
+    
+    // Sender asks the recipient for a refund, shortly after tipping
+    RequestRefund {
+        tip_id: String,
+        reason: Option<String>,
+        username: Option<String>,
+    },
+    
+    // Sender takes back a tip held for a username that was not registered in time
+    ReclaimUnregisteredTip {
+        username: String,
+        tip_id: u64,
+    },
+    
+    // Fundraising goals on the sender's profile
+    CreateGoal {
+        description: String,
+        target: Coin,
+        deadline: Timestamp,
+        username: Option<String>,
+    },
+    
+    CloseGoal {
+        goal_id: u64,
+        username: Option<String>,
+    },
+    
+    // Admin management (owner only); grants are merged with existing roles.
+    // The owner role itself is only handed over through ProposeOwnership.
     AddAdmin {
         admin: String,
+        roles: Vec<Role>,
     },
     
+    // Revokes the given roles from another admin, or every role when `roles` is None
     RemoveAdmin {
         admin: String,
+        roles: Option<Vec<Role>>,
+    },
+    
+    // Drops every role held by the sender; the last owner cannot renounce
+    RenounceAdmin {},
+    
+    // Shorthands for granting or revoking only the verifier role (owner only)
+    AddVerifier {
+        verifier: String,
+    },
+    
+    RemoveVerifier {
+        verifier: String,
+    },
+    
+    // Two-step owner handover: the current owner proposes, the new owner accepts
+    ProposeOwnership {
+        new_owner: String,
+    },
+    
+    AcceptOwnership {},
+    
+    CancelOwnershipProposal {},
+    
+    // Subscriptions: creators define tiers, fans prepay periods into escrow
+    CreateSubscriptionTier {
+        name: String,
+        price: Coin,
+        period_seconds: u64,
+        perks: Option<String>,
+        username: Option<String>,
+    },
+    
+    DeactivateSubscriptionTier {
+        tier_id: u64,
+        username: Option<String>,
+    },
+    
+    // Attach price * periods of the tier's denom
+    Subscribe {
+        creator: String,
+        tier_id: u64,
+        periods: u64,
+    },
+    
+    // Refunds the periods that have not started yet
+    CancelSubscription {
+        subscription_id: u64,
+    },
+    
+    // Pays matured periods of the sender's subscribers to the sender's wallet
+    ClaimSubscriptions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        username: Option<String>,
+    },
+    
+    // All-or-nothing crowdfunding on the sender's profile
+    CreateCampaign {
+        description: String,
+        target: Coin,
+        deadline: Timestamp,
+        username: Option<String>,
+    },
+    
+    // Attach the pledge in the campaign's denom
+    Pledge {
+        campaign_id: u64,
+    },
+    
+    // Creator collects the pledges once the campaign succeeded
+    ClaimCampaignFunds {
+        campaign_id: u64,
+    },
+    
+    // Pledger takes back their contribution once the campaign failed
+    ClaimRefund {
+        campaign_id: u64,
+    },
+    
+    // Streaming tips: attach the funds to vest linearly over `duration_seconds`
+    CreateStream {
+        to_username: String,
+        duration_seconds: u64,
+        message: Option<String>,
+        username: Option<String>,
+    },
+    
+    // Recipient withdraws the vested, unwithdrawn portion
+    WithdrawStream {
+        stream_id: u64,
+    },
+    
+    // Sender stops the stream and reclaims the unvested remainder
+    CancelStream {
+        stream_id: u64,
+    },
+    
+    // Recurring tips: attach a budget that pays `amount` every `interval_seconds`, starting
+    // at `start_at` (default now). Each execution also pays the keeper reward from the budget.
+    CreateScheduledTip {
+        to_username: String,
+        amount: Coin,
+        interval_seconds: u64,
+        start_at: Option<Timestamp>,
+        message: Option<String>,
+        username: Option<String>,
+    },
+    
+    // Sender stops the schedule and withdraws the remaining budget
+    CancelScheduledTip {
+        schedule_id: u64,
+    },
+    
+    // Anyone can process up to `limit` due scheduled tips and earn the keeper reward
+    ExecuteDueTips {
+        limit: Option<u32>,
+    },
+    
+    // Contract settings (config admins); omitted fields keep their current value
+    UpdateConfig(ConfigUpdate),
+    
+    // Send accumulated platform fees to `recipient` (treasurers), defaulting to the sender
+    WithdrawFees {
+        recipient: Option<String>,
+    },
+    
+    // Emergency pause (config admins); omitted flags keep their current value
+    SetPauseStatus {
+        registrations: Option<bool>,
+        profile_updates: Option<bool>,
+        tips: Option<bool>,
+    },
+    
+    // Verification management
+    VerifyProfile {
+        username: String,
+        category: String,
+        expires_at: Option<Timestamp>,
+    },
+    
+    RevokeVerification {
+        username: String,
+    },
+    
+    // Moderation
+    SuspendProfile {
+        username: String,
+        reason: String,
+    },
+    
+    UnsuspendProfile {
+        username: String,
+        reason: String,
+    },
+    
+    RedactTipMessage {
+        from_username: String,
+        to_username: String,
+        timestamp: Timestamp,
+        reason: String,
+    },
+    
+    RedactTipReply {
+        tip_id: String,
+        reason: String,
+    },
+    
+    // Blocklist management for the sender's own profile
+    Block {
+        target: BlockTarget,
+        username: Option<String>,
+    },
+    
+    Unblock {
+        target: BlockTarget,
+        username: Option<String>,
+    },
+    
+    SetBlockPolicy {
+        policy: BlockPolicy,
+        username: Option<String>,
+    },
+    
+    // Hook called by the profile NFT contract when a username token is sent to this contract
+    ReceiveNft(Cw721ReceiveMsg),
+    
+    // Mint the profile NFT for a username registered before profile NFTs were enabled
+    ClaimProfileNft {
+        username: Option<String>,
+    },
+    
+    // Point a profile at the current owner of its NFT, for tokens moved with a plain transfer
+    SyncProfileOwner {
+        username: String,
+    },
+    
+    // Add the attached funds to the sender's deposited balance
+    Deposit {},
+    
+    WithdrawDeposit {
+        amount: Coin,
+    },
+    
+    // Let `delegate` tip from the sender's deposited balance, up to `spend_limit` per period.
+    // Granting again replaces the allowance and resets its spending.
+    GrantTipAllowance {
+        delegate: String,
+        spend_limit: Vec<Coin>,
+        period_seconds: u64,
+        allowed_recipients: Option<Vec<String>>,
+        expires_at: Option<Timestamp>,
+    },
+    
+    RevokeTipAllowance {
+        delegate: String,
+    },
+    
+    // Tip from the deposited balance of the wallet owning `from_username`, within the
+    // allowance it granted the sender. The tip is recorded as sent by `from_username`.
+    TipOnBehalf {
+        from_username: String,
+        to_username: String,
+        amount: Coin,
+        message: Option<String>,
+    },
+    
+    // Username marketplace. Usernames backed by a profile NFT are listed and sold by sending
+    // the token with a `ProfileNftHookMsg` instead of `ListUsername`/`AcceptOffer`.
+    ListUsername {
+        price: Coin,
+        keep_profile_data: bool,
+        username: Option<String>,
+    },
+    
+    CancelListing {
+        username: Option<String>,
+    },
+    
+    // Buy a listed username; attached funds must equal the price
+    BuyUsername {
+        username: String,
+    },
+    
+    // Escrow the attached funds as an offer for a username
+    MakeOffer {
+        username: String,
+    },
+    
+    WithdrawOffer {
+        username: String,
+        offer_id: u64,
+    },
+    
+    AcceptOffer {
+        offer_id: u64,
+        keep_profile_data: bool,
+        username: Option<String>,
+    },
+    
+    // Supporters tipping at least `threshold` receive a receipt NFT; `None` turns receipts off
+    SetReceiptThreshold {
+        threshold: Option<Coin>,
+        username: Option<String>,
     },
 }
 
+#[cw_serde]
+pub struct BatchTipEntry {
+    pub to_username: String,
+    pub amount: String,
+    pub message: Option<String>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub escrow_timeout_seconds: Option<u64>,
+    pub unregistered_tip_expiry_seconds: Option<u64>,
+    pub keeper_reward_bps: Option<u64>,
+    pub refund_request_window_seconds: Option<u64>,
+    pub platform_fee_bps: Option<u64>,
+    pub referral_share_bps: Option<u64>,
+    pub referral_period_seconds: Option<u64>,
+    pub receipt_nft_contract: Option<String>,
+    pub profile_nft_contract: Option<String>,
+    pub marketplace_fee_bps: Option<u64>,
+    // A zero amount makes registration free
+    pub registration_fee: Option<Coin>,
+    pub registration_period_seconds: Option<u64>,
+    pub registration_grace_seconds: Option<u64>,
+}
+
+// Subset of the cw721-base execute interface used for receipts and profile NFTs
+#[cw_serde]
+pub enum Cw721ExecuteMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: NftMetadata,
+    },
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+}
+
+// Subset of the cw721-base query interface
+#[cw_serde]
+pub enum Cw721QueryMsg {
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+}
+
+// Owner part of the cw721 OwnerOf response; approvals are ignored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Cw721OwnerOfResponse {
+    pub owner: String,
+}
+
+// Payload cw721 contracts deliver with SendNft
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+// Messages accepted in `Cw721ReceiveMsg::msg` for profile NFTs
+#[cw_serde]
+pub enum ProfileNftHookMsg {
+    // Hand the profile over to `recipient`, who receives the token
+    TransferProfile { recipient: String },
+    // List the username for sale; the contract holds the token until it is sold or unlisted
+    ListProfile { price: Coin, keep_profile_data: bool },
+    // Sell the username to an open offer
+    AcceptOffer { offer_id: u64, keep_profile_data: bool },
+}
+
+// On-chain metadata of minted NFTs, in the cw721-metadata-onchain layout
+#[cw_serde]
+pub struct NftMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub attributes: Option<Vec<NftTrait>>,
+}
+
+#[cw_serde]
+pub struct NftTrait {
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -62,10 +541,22 @@ pub enum QueryMsg {
         wallet: String 
     },
     
+    // Every profile owned by a wallet, primary first
+    #[returns(ProfilesResponse)]
+    GetProfilesByWallet {
+        wallet: String,
+    },
+    
+    #[returns(ProfileManagersResponse)]
+    GetProfileManagers {
+        username: String,
+    },
+    
     #[returns(ProfilesResponse)]
     ListProfiles { 
         limit: Option<u32>,
         start_after: Option<String>,
+        verified_only: Option<bool>,
     },
     
     // Tip queries
@@ -83,6 +574,85 @@ pub enum QueryMsg {
         start_after: Option<String>,
     },
     
+    // Public wall: pinned tips (first page only) followed by the newest public tips
+    #[returns(TipWallResponse)]
+    GetTipWall {
+        username: String,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    
+    // Delegated tipping queries
+    #[returns(DepositsResponse)]
+    GetDeposits {
+        wallet: String,
+    },
+    
+    #[returns(TipAllowanceResponse)]
+    GetTipAllowance {
+        granter: String,
+        delegate: String,
+    },
+    
+    #[returns(TipAllowancesResponse)]
+    GetTipAllowances {
+        granter: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    
+    // Marketplace queries
+    #[returns(ListingResponse)]
+    GetListing {
+        username: String,
+    },
+    
+    #[returns(ListingsResponse)]
+    GetListings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    
+    #[returns(OffersResponse)]
+    GetOffers {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    // Receipt NFT minted for a tip, if any
+    #[returns(TipReceiptResponse)]
+    GetTipReceipt {
+        tip_id: String,
+    },
+    
+    #[returns(ReceiptThresholdResponse)]
+    GetReceiptThreshold {
+        username: String,
+    },
+    
+    // Escrowed tips waiting for the recipient to claim them
+    #[returns(TipsResponse)]
+    GetPendingTips {
+        username: String,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    
+    // Open refund requests on tips received by `username`
+    #[returns(RefundRequestsResponse)]
+    GetRefundRequests {
+        username: String,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    
+    // Funded tips waiting for `username` to be registered
+    #[returns(UnregisteredNameTipsResponse)]
+    GetUnregisteredNameTips {
+        username: String,
+    },
+    
     #[returns(TipDetailResponse)]
     GetTipDetail { 
         from_username: String,
@@ -90,6 +660,101 @@ pub enum QueryMsg {
         timestamp: Timestamp,
     },
     
+    // Subscription queries
+    #[returns(SubscriptionTiersResponse)]
+    GetSubscriptionTiers {
+        creator: String,
+    },
+    
+    // Active subscribers of a creator
+    #[returns(SubscriptionsResponse)]
+    GetSubscribers {
+        creator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    // All subscriptions paid by a wallet
+    #[returns(SubscriptionsResponse)]
+    GetSubscriptions {
+        subscriber: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    // Fundraising goals of a profile, with progress
+    #[returns(GoalsResponse)]
+    GetGoals {
+        username: String,
+    },
+    
+    // Crowdfunding queries
+    #[returns(CampaignResponse)]
+    GetCampaign {
+        campaign_id: u64,
+    },
+    
+    #[returns(CampaignsResponse)]
+    GetCampaigns {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    #[returns(PledgeResponse)]
+    GetPledge {
+        campaign_id: u64,
+        pledger: String,
+    },
+    
+    // Stream queries
+    #[returns(StreamResponse)]
+    GetStream {
+        stream_id: u64,
+    },
+    
+    #[returns(StreamsResponse)]
+    GetStreamsSent {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    #[returns(StreamsResponse)]
+    GetStreamsReceived {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    // Scheduled tip queries
+    #[returns(ScheduledTipResponse)]
+    GetScheduledTip {
+        schedule_id: u64,
+    },
+    
+    #[returns(ScheduledTipsResponse)]
+    GetScheduledTipsSent {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    
+    // Active schedules that are due now, for keepers
+    #[returns(ScheduledTipsResponse)]
+    GetDueScheduledTips {
+        limit: Option<u32>,
+    },
+    
+    // Blocklist and block policy of a profile, paginated by BlockTarget::key(). Blocklists
+    // are public: anyone can read who a profile has blocked.
+    #[returns(BlockedResponse)]
+    GetBlocked {
+        username: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    
     // Statistics
     #[returns(StatsResponse)]
     GetUserStats {
@@ -102,17 +767,65 @@ pub enum QueryMsg {
         address: String,
     },
     
+    #[returns(VerifierResponse)]
+    IsVerifier {
+        address: String,
+    },
+    
+    #[returns(RolesResponse)]
+    GetRoles {
+        address: String,
+    },
+    
+    #[returns(AdminsResponse)]
+    ListAdmins {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    
+    #[returns(OwnershipProposalResponse)]
+    GetOwnershipProposal {},
+    
+    #[returns(PauseStatusResponse)]
+    GetPauseStatus {},
+    
+    #[returns(ConfigResponse)]
+    GetConfig {},
+    
+    // Platform fees held by the contract
+    #[returns(PlatformFeesResponse)]
+    GetPlatformFees {},
+    
+    // Profiles referred by `username` and the fee share earned from them
+    #[returns(ReferralsResponse)]
+    GetReferrals {
+        username: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    
     // Utility
     #[returns(UsernameAvailableResponse)]
     IsUsernameAvailable {
         username: String,
     },
+    
+    // Lapsed registrations of a username, oldest first
+    #[returns(ArchivedProfilesResponse)]
+    GetArchivedProfiles {
+        username: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 // Response types
 #[cw_serde]
 pub struct ProfileResponse {
     pub profile: Option<UserProfile>,
+    pub verification: Option<Verification>,
+    pub is_verified: bool,
+    pub suspension: Option<ModerationAction>,
 }
 
 #[cw_serde]
@@ -120,22 +833,172 @@ pub struct ProfilesResponse {
     pub profiles: Vec<UserProfile>,
 }
 
+#[cw_serde]
+pub struct ProfileManagersResponse {
+    pub managers: Vec<Addr>,
+}
+
 #[cw_serde]
 pub struct TipsResponse {
     pub tips: Vec<TipRecord>,
 }
 
+#[cw_serde]
+pub struct DepositsResponse {
+    pub balances: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct TipAllowanceResponse {
+    pub allowance: Option<TipAllowance>,
+}
+
+#[cw_serde]
+pub struct TipAllowancesResponse {
+    pub allowances: Vec<TipAllowance>,
+}
+
+#[cw_serde]
+pub struct ListingResponse {
+    pub listing: Option<Listing>,
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+#[cw_serde]
+pub struct OffersResponse {
+    pub offers: Vec<Offer>,
+}
+
+#[cw_serde]
+pub struct TipReceiptResponse {
+    pub token_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct ReceiptThresholdResponse {
+    pub threshold: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct TipWallResponse {
+    pub pinned: Vec<TipRecord>,
+    pub tips: Vec<TipRecord>,
+}
+
+#[cw_serde]
+pub struct RefundRequestsResponse {
+    pub requests: Vec<RefundRequest>,
+}
+
+#[cw_serde]
+pub struct UnregisteredNameTipsResponse {
+    pub tips: Vec<UnregisteredNameTip>,
+}
+
 #[cw_serde]
 pub struct TipDetailResponse {
     pub tip: Option<TipRecord>,
 }
 
+#[cw_serde]
+pub struct SubscriptionTiersResponse {
+    pub tiers: Vec<SubscriptionTier>,
+}
+
+#[cw_serde]
+pub struct SubscriptionsResponse {
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[cw_serde]
+pub struct GoalInfo {
+    pub goal: FundraisingGoal,
+    pub status: GoalStatus,
+}
+
+#[cw_serde]
+pub struct GoalsResponse {
+    pub goals: Vec<GoalInfo>,
+}
+
+#[cw_serde]
+pub struct CampaignInfo {
+    pub campaign: Campaign,
+    pub status: CampaignStatus,
+}
+
+#[cw_serde]
+pub struct CampaignResponse {
+    pub campaign: Option<CampaignInfo>,
+}
+
+#[cw_serde]
+pub struct CampaignsResponse {
+    pub campaigns: Vec<CampaignInfo>,
+}
+
+#[cw_serde]
+pub struct PledgeResponse {
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct StreamResponse {
+    pub stream: Option<TipStream>,
+    pub vested: Uint128,
+    pub withdrawable: Uint128,
+}
+
+#[cw_serde]
+pub struct StreamsResponse {
+    pub streams: Vec<TipStream>,
+}
+
+#[cw_serde]
+pub struct ScheduledTipResponse {
+    pub schedule: Option<ScheduledTip>,
+}
+
+#[cw_serde]
+pub struct ScheduledTipsResponse {
+    pub schedules: Vec<ScheduledTip>,
+}
+
+#[cw_serde]
+pub struct BlockedResponse {
+    pub policy: BlockPolicy,
+    pub blocked: Vec<BlockedEntry>,
+}
+
 #[cw_serde]
 pub struct StatsResponse {
     pub total_tips_sent: u64,
     pub total_tips_received: u64,
     pub total_amount_sent: String,
     pub total_amount_received: String,
+    pub referral_earnings: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ReferralInfo {
+    pub username: String,
+    pub registered_at: Timestamp,
+    pub referral_ends_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct ReferralsResponse {
+    pub referrals: Vec<ReferralInfo>,
+    pub earnings: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct PlatformFeesResponse {
+    pub fees: Vec<Coin>,
 }
 
 #[cw_serde]
@@ -143,7 +1006,50 @@ pub struct AdminResponse {
     pub is_admin: bool,
 }
 
+#[cw_serde]
+pub struct VerifierResponse {
+    pub is_verifier: bool,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct AdminInfo {
+    pub address: String,
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct AdminsResponse {
+    pub admins: Vec<AdminInfo>,
+}
+
+#[cw_serde]
+pub struct OwnershipProposalResponse {
+    pub proposal: Option<OwnershipProposal>,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub status: PauseStatus,
+    pub any_paused: bool,
+}
+
 #[cw_serde]
 pub struct UsernameAvailableResponse {
     pub is_available: bool,
+    pub registration_fee: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct ArchivedProfilesResponse {
+    pub archives: Vec<ArchivedProfile>,
 }
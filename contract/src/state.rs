@@ -1,5 +1,7 @@
-use cosmwasm_std::{Addr, Timestamp};
-use cw_storage_plus::Map;
+use std::fmt;
+
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp, Uint128};
+use cw_storage_plus::{Deque, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +18,26 @@ pub struct UserProfile {
     pub wallet_address: Addr,       // User's wallet address
     pub created_at: Timestamp,      // When the profile was created
     pub updated_at: Timestamp,      // When the profile was last updated
+    #[serde(default)]
+    pub encryption_key: Option<EncryptionKey>, // Public key fans use to encrypt private tip messages
+    #[serde(default)]
+    pub referrer: Option<String>,   // Username of the creator who referred this profile
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>, // When the registration lapses unless renewed; None never lapses
+}
+
+// Public key published by a creator for private tip messages
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct EncryptionKey {
+    pub scheme: String,             // Encryption scheme identifier (e.g., "x25519-xsalsa20-poly1305")
+    pub public_key: Binary,         // Public key bytes
+}
+
+// Private tip message; the contract only stores the ciphertext, it cannot read it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct EncryptedMessage {
+    pub scheme: String,             // Scheme of the recipient key the message was encrypted to
+    pub ciphertext: Binary,         // Opaque encrypted message
 }
 
 // Tip record structure
@@ -26,6 +48,96 @@ pub struct TipRecord {
     pub amount: String,             // Amount as string (e.g., "5uxion")
     pub message: Option<String>,    // Optional message with the tip
     pub timestamp: Timestamp,       // When the tip was recorded
+    #[serde(default)]
+    pub moderation: Option<ModerationAction>, // Set when an admin redacted the message
+    #[serde(default)]
+    pub goal_id: Option<u64>,       // Fundraising goal the tip was earmarked for
+    #[serde(default)]
+    pub status: TipStatus,          // Settlement state of the tip's funds
+    #[serde(default)]
+    pub reply: Option<TipReply>,    // Recipient's public reply to the tip
+    #[serde(default)]
+    pub encrypted_message: Option<EncryptedMessage>, // Private message only the recipient can decrypt
+    #[serde(default)]
+    pub visibility: TipVisibility,  // How the tip is shown on the recipient's public wall
+    #[serde(default)]
+    pub paid_by: Option<Addr>,      // Wallet whose funds paid for the tip, None for record-only tips
+    #[serde(default)]
+    pub delivered: Option<Coin>,    // What reached the recipient after platform fees
+}
+
+// Display flags for a tip on the recipient's public wall
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct TipVisibility {
+    pub creator_only: bool,         // Sender keeps the tip off the public wall
+    pub hidden: bool,               // Recipient hid the tip from the public wall
+    pub pinned: bool,               // Recipient pinned the tip to the top of the wall
+}
+
+impl TipVisibility {
+    // Whether the tip may appear on the public wall
+    pub fn is_public(&self) -> bool {
+        !self.creator_only && !self.hidden
+    }
+}
+
+// Index of tips pinned by a recipient - key: (recipient username, tip id)
+pub const PINNED_TIPS: Map<(&str, &str), bool> = Map::new("pinned_tips");
+
+// Recipient's reply to a tip, shown alongside the tip message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TipReply {
+    pub message: Option<String>,    // Reply text, cleared when redacted
+    pub replied_at: Timestamp,      // When the reply was first posted
+    pub edited_at: Option<Timestamp>, // When the reply was last edited
+    pub moderation: Option<ModerationAction>, // Set when an admin redacted the reply
+}
+
+// Settlement state of a tip
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TipStatus {
+    #[default]
+    Completed,                      // Recorded, or funds forwarded straight to the recipient
+    Pending,                        // Funds held in escrow until the recipient claims them
+    Claimed,                        // Escrowed funds released to the recipient
+    Reclaimed,                      // Escrowed funds returned to the sender after the timeout
+    Refunded,                       // Funds returned to the sender by the recipient
+}
+
+impl TipStatus {
+    // Whether the tip still counts as money moved from sender to recipient
+    pub fn counts_towards_totals(&self) -> bool {
+        !matches!(self, TipStatus::Reclaimed | TipStatus::Refunded)
+    }
+}
+
+// Sender's request to have a tip returned, awaiting the recipient's decision
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RefundRequest {
+    pub tip_id: String,             // Tip the sender wants back
+    pub from_username: String,      // Username of the sender
+    pub reason: Option<String>,     // Optional explanation for the recipient
+    pub requested_at: Timestamp,    // When the refund was requested
+}
+
+// Store open refund requests - key: (recipient username, tip id)
+pub const REFUND_REQUESTS: Map<(&str, &str), RefundRequest> = Map::new("refund_requests");
+
+// Funds held by the contract for a tip in escrow mode
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct EscrowedTip {
+    pub sender: Addr,               // Wallet that attached the funds
+    pub funds: Coin,                // Escrowed funds
+    pub reclaimable_at: Timestamp,  // When the sender may take unclaimed funds back
+}
+
+// Moderation note recorded when an admin acts on a profile or a tip
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ModerationAction {
+    pub reason: String,             // Why the action was taken
+    pub moderated_by: Addr,         // Admin that took the action
+    pub moderated_at: Timestamp,    // When the action was taken
 }
 
 // Store user profiles by username
@@ -34,6 +146,12 @@ pub const USER_PROFILES: Map<&str, UserProfile> = Map::new("user_profiles");
 // Store wallet addresses to usernames mapping (for quick lookup)
 pub const WALLET_TO_USERNAME: Map<&Addr, String> = Map::new("wallet_to_username");
 
+// Index of every profile a wallet owns - key: (wallet, username)
+pub const WALLET_PROFILES: Map<(&Addr, &str), bool> = Map::new("wallet_profiles");
+
+// Index of wallets allowed to edit a profile on its owner's behalf - key: (username, manager)
+pub const PROFILE_MANAGERS: Map<(&str, &Addr), bool> = Map::new("profile_managers");
+
 // Store tip records - using a composite string key instead of timestamp directly
 // Key format: "from_username:to_username:timestamp.nanos()"
 pub const TIP_RECORDS: Map<&str, TipRecord> = Map::new("tip_records");
@@ -44,5 +162,510 @@ pub const TIPS_SENT: Map<&str, Vec<(String, String)>> = Map::new("tips_sent");
 // Store tips received by a user
 pub const TIPS_RECEIVED: Map<&str, Vec<(String, String)>> = Map::new("tips_received");
 
-// Store global contract admins
-pub const CONTRACT_ADMINS: Map<&Addr, bool> = Map::new("contract_admins");
+// Tips received by a user ordered by time: (to_username, timestamp nanos, tip key)
+pub const TIPS_RECEIVED_BY_TIME: Map<(&str, u64, &str), ()> = Map::new("tips_received_by_time");
+
+// Admin roles; an owner implicitly holds every other role
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Owner,                          // Manages roles and can do everything
+    ConfigAdmin,                    // Changes contract-wide settings
+    Moderator,                      // Suspends profiles, redacts and edits content
+    Verifier,                       // Issues and revokes verification badges
+    Treasurer,                      // Manages contract-held funds
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Role::Owner => "owner",
+            Role::ConfigAdmin => "config_admin",
+            Role::Moderator => "moderator",
+            Role::Verifier => "verifier",
+            Role::Treasurer => "treasurer",
+        };
+        f.write_str(name)
+    }
+}
+
+// Store the roles held by each admin address (sorted, never empty)
+pub const ADMIN_ROLES: Map<&Addr, Vec<Role>> = Map::new("admin_roles");
+
+// Admin and verifier sets from before roles existed, only read by migrate
+pub const LEGACY_CONTRACT_ADMINS: Map<&Addr, bool> = Map::new("contract_admins");
+pub const LEGACY_VERIFIERS: Map<&Addr, bool> = Map::new("verifiers");
+
+// Pending two-step transfer of the owner role
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OwnershipProposal {
+    pub proposer: Addr,             // Owner handing over the role
+    pub new_owner: Addr,            // Address that must accept the role
+    pub proposed_at: Timestamp,     // When the handover was proposed
+}
+
+// Store the pending ownership handover, if any
+pub const PENDING_OWNERSHIP: Item<OwnershipProposal> = Item::new("pending_ownership");
+
+// Verification badge attached to a profile by an admin or verifier
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Verification {
+    pub category: String,           // Kind of verification (e.g. "creator", "brand")
+    pub verified_by: Addr,          // Admin or verifier that issued the badge
+    pub verified_at: Timestamp,     // When the badge was issued
+    pub expires_at: Option<Timestamp>, // When the badge stops being valid, if ever
+}
+
+impl Verification {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now < expires_at,
+            None => true,
+        }
+    }
+}
+
+// Store verification badges by username
+pub const PROFILE_VERIFICATIONS: Map<&str, Verification> = Map::new("profile_verifications");
+
+// Store suspended profiles by username
+pub const SUSPENDED_PROFILES: Map<&str, ModerationAction> = Map::new("suspended_profiles");
+
+// Party a creator can block from tipping them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockTarget {
+    Username(String),
+    Address(String),
+}
+
+impl BlockTarget {
+    // Storage key for the blocklist; usernames and addresses never contain ':'
+    pub fn key(&self) -> String {
+        match self {
+            BlockTarget::Username(username) => format!("username:{}", username),
+            BlockTarget::Address(address) => format!("address:{}", address),
+        }
+    }
+}
+
+// Blocklist entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BlockedEntry {
+    pub target: BlockTarget,        // Blocked username or address
+    pub blocked_at: Timestamp,      // When the entry was added
+}
+
+// What happens to a tip coming from a blocked party
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockPolicy {
+    #[default]
+    RejectTip,                      // Refuse the tip entirely
+    DropMessage,                    // Record the tip but discard its message
+}
+
+// Store blocklists - key: (owner username, BlockTarget::key())
+// Blocklists are public and served by the GetBlocked query
+pub const BLOCKLIST: Map<(&str, &str), BlockedEntry> = Map::new("blocklist");
+
+// Store each creator's block policy, defaults to rejecting tips
+pub const BLOCK_POLICIES: Map<&str, BlockPolicy> = Map::new("block_policies");
+
+// Contract-wide circuit breaker flags
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct PauseStatus {
+    pub registrations: bool,        // Blocks RegisterProfile
+    pub profile_updates: bool,      // Blocks UpdateProfile
+    pub tips: bool,                 // Blocks RecordTip
+}
+
+// Store the pause flags; missing means nothing is paused
+pub const PAUSE_STATUS: Item<PauseStatus> = Item::new("pause_status");
+
+// Membership tier offered by a creator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SubscriptionTier {
+    pub id: u64,                    // Tier identifier
+    pub creator: String,            // Username of the creator offering the tier
+    pub name: String,               // Tier display name
+    pub price: Coin,                // Price per period
+    pub period_seconds: u64,        // Length of one period
+    pub perks: Option<String>,      // Description of what supporters get
+    pub active: bool,               // Whether new subscriptions are accepted
+    pub created_at: Timestamp,      // When the tier was created
+}
+
+// Prepaid subscription held in escrow by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Subscription {
+    pub id: u64,                    // Subscription identifier
+    pub subscriber: Addr,           // Wallet that paid for the subscription
+    pub creator: String,            // Username of the creator
+    pub tier_id: u64,               // Tier subscribed to
+    pub price: Coin,                // Price per period at subscription time
+    pub period_seconds: u64,        // Period length at subscription time
+    pub started_at: Timestamp,      // Start of the first period
+    pub periods_paid: u64,          // Periods prepaid (reduced on cancellation)
+    pub periods_claimed: u64,       // Periods already paid out to the creator
+    pub cancelled_at: Option<Timestamp>, // When the subscriber cancelled, if ever
+}
+
+impl Subscription {
+    // Number of whole periods elapsed since the start, capped at what was paid
+    pub fn matured_periods(&self, now: Timestamp) -> u64 {
+        let elapsed = now.seconds().saturating_sub(self.started_at.seconds());
+        (elapsed / self.period_seconds).min(self.periods_paid)
+    }
+
+    // Whether the subscription still grants membership
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.cancelled_at.is_none() && self.matured_periods(now) < self.periods_paid
+    }
+}
+
+// Store subscription tiers - key: (creator username, tier id)
+pub const SUBSCRIPTION_TIERS: Map<(&str, u64), SubscriptionTier> = Map::new("subscription_tiers");
+
+// Store the last issued subscription tier id
+pub const SUBSCRIPTION_TIER_SEQ: Item<u64> = Item::new("subscription_tier_seq");
+
+// Store subscriptions by id
+pub const SUBSCRIPTIONS: Map<u64, Subscription> = Map::new("subscriptions");
+
+// Store the last issued subscription id
+pub const SUBSCRIPTION_SEQ: Item<u64> = Item::new("subscription_seq");
+
+// Index of subscriptions with unclaimed periods by creator - key: (creator username, subscription id)
+pub const CREATOR_SUBSCRIPTIONS: Map<(&str, u64), bool> = Map::new("creator_subscriptions");
+
+// Index of subscriptions by subscriber - key: (subscriber wallet, subscription id)
+pub const SUBSCRIBER_SUBSCRIPTIONS: Map<(&Addr, u64), bool> = Map::new("subscriber_subscriptions");
+
+// Tip streamed linearly to a creator, escrowed by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TipStream {
+    pub id: u64,                    // Stream identifier
+    pub sender: Addr,               // Wallet that funded the stream
+    pub from_username: String,      // Username of the sender
+    pub to_username: String,        // Username of the recipient
+    pub denom: String,              // Denomination of the escrowed funds
+    pub total: Uint128,             // Amount streamed over the whole duration
+    pub withdrawn: Uint128,         // Amount already paid out to the recipient
+    pub start: Timestamp,           // When vesting starts
+    pub end: Timestamp,             // When the whole amount is vested
+    pub message: Option<String>,    // Optional message, attached to the first withdrawal
+    pub cancelled_at: Option<Timestamp>, // When the sender cancelled, if ever
+}
+
+impl TipStream {
+    // Amount vested at `now`, growing linearly from start to end
+    pub fn vested(&self, now: Timestamp) -> Uint128 {
+        if now >= self.end || self.end <= self.start {
+            return self.total;
+        }
+        let elapsed = now.seconds().saturating_sub(self.start.seconds());
+        let duration = self.end.seconds() - self.start.seconds();
+        self.total.multiply_ratio(elapsed, duration)
+    }
+}
+
+// Store tip streams by id
+pub const TIP_STREAMS: Map<u64, TipStream> = Map::new("tip_streams");
+
+// Store the last issued stream id
+pub const TIP_STREAM_SEQ: Item<u64> = Item::new("tip_stream_seq");
+
+// Index of streams by sender username - key: (username, stream id)
+pub const STREAMS_SENT: Map<(&str, u64), bool> = Map::new("streams_sent");
+
+// Index of streams by recipient username - key: (username, stream id)
+pub const STREAMS_RECEIVED: Map<(&str, u64), bool> = Map::new("streams_received");
+
+// Recurring tip paid from a budget deposited by the sender
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ScheduledTip {
+    pub id: u64,                    // Schedule identifier
+    pub sender: Addr,               // Wallet that deposited the budget
+    pub from_username: String,      // Username of the sender
+    pub to_username: String,        // Username of the recipient
+    pub amount: Coin,               // Amount tipped on each execution
+    pub interval_seconds: u64,      // Time between executions
+    pub next_run_at: Timestamp,     // When the next execution is due
+    pub budget: Uint128,            // Remaining deposit, covering tips and keeper rewards
+    pub executions: u64,            // Number of tips paid so far
+    pub message: Option<String>,    // Optional message recorded with every tip
+    pub ended_at: Option<Timestamp>, // When the schedule was cancelled or ran out of budget
+}
+
+// Store scheduled tips by id
+pub const SCHEDULED_TIPS: Map<u64, ScheduledTip> = Map::new("scheduled_tips");
+
+// Store the last issued schedule id
+pub const SCHEDULED_TIP_SEQ: Item<u64> = Item::new("scheduled_tip_seq");
+
+// Index of scheduled tips by sender username - key: (username, schedule id)
+pub const SCHEDULED_TIPS_SENT: Map<(&str, u64), bool> = Map::new("scheduled_tips_sent");
+
+// Index of scheduled tips by recipient username - key: (username, schedule id)
+pub const SCHEDULED_TIPS_RECEIVED: Map<(&str, u64), bool> = Map::new("scheduled_tips_received");
+
+// Queue of active schedules ordered by due time - key: (next run in seconds, schedule id)
+pub const DUE_SCHEDULED_TIPS: Map<(u64, u64), bool> = Map::new("due_scheduled_tips");
+
+// Fundraising goal attached to a profile
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FundraisingGoal {
+    pub id: u64,                    // Goal identifier
+    pub username: String,           // Creator the goal belongs to
+    pub description: String,        // What the funds are for
+    pub target: Uint128,            // Amount to raise
+    pub denom: String,              // Denomination counted towards the target
+    pub deadline: Timestamp,        // Last moment earmarked tips are accepted
+    pub raised: Uint128,            // Amount earmarked so far
+    pub created_at: Timestamp,      // When the goal was created
+    pub completed_at: Option<Timestamp>, // When the target was first reached
+    pub closed_at: Option<Timestamp>, // When the creator closed the goal early
+}
+
+// Lifecycle of a fundraising goal
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Active,                         // Accepting earmarked tips
+    Completed,                      // Target reached
+    Expired,                        // Deadline passed or closed without reaching the target
+}
+
+impl FundraisingGoal {
+    pub fn status(&self, now: Timestamp) -> GoalStatus {
+        if self.completed_at.is_some() {
+            GoalStatus::Completed
+        } else if !self.accepts_tips(now) {
+            GoalStatus::Expired
+        } else {
+            GoalStatus::Active
+        }
+    }
+    
+    // Earmarks are taken until the deadline or an early close, even past the target
+    pub fn accepts_tips(&self, now: Timestamp) -> bool {
+        self.closed_at.is_none() && now <= self.deadline
+    }
+}
+
+// Store fundraising goals - key: (username, goal id)
+pub const GOALS: Map<(&str, u64), FundraisingGoal> = Map::new("goals");
+
+// Store the last issued goal id
+pub const GOAL_SEQ: Item<u64> = Item::new("goal_seq");
+
+// All-or-nothing crowdfunding campaign; pledges are held by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Campaign {
+    pub id: u64,                    // Campaign identifier
+    pub creator: String,            // Username of the creator raising funds
+    pub description: String,        // What the funds are for
+    pub target: Uint128,            // Amount that must be pledged by the deadline
+    pub denom: String,              // Denomination of pledges
+    pub deadline: Timestamp,        // End of the pledging window
+    pub pledged: Uint128,           // Total currently pledged
+    pub created_at: Timestamp,      // When the campaign was created
+    pub funds_released: bool,       // Whether the creator claimed the pledges
+    #[serde(default)]
+    pub cancelled_at: Option<Timestamp>, // When the campaign was called off because its creator's registration lapsed
+}
+
+// Outcome of a crowdfunding campaign
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignStatus {
+    Open,                           // Accepting pledges
+    Succeeded,                      // Target reached by the deadline, creator can claim
+    Failed,                         // Target missed, pledgers can claim refunds
+}
+
+impl Campaign {
+    pub fn status(&self, now: Timestamp) -> CampaignStatus {
+        if self.cancelled_at.is_some() {
+            CampaignStatus::Failed
+        } else if now <= self.deadline {
+            CampaignStatus::Open
+        } else if self.pledged >= self.target {
+            CampaignStatus::Succeeded
+        } else {
+            CampaignStatus::Failed
+        }
+    }
+}
+
+// Store campaigns by id
+pub const CAMPAIGNS: Map<u64, Campaign> = Map::new("campaigns");
+
+// Store the last issued campaign id
+pub const CAMPAIGN_SEQ: Item<u64> = Item::new("campaign_seq");
+
+// Index of campaigns by creator - key: (username, campaign id)
+pub const CREATOR_CAMPAIGNS: Map<(&str, u64), bool> = Map::new("creator_campaigns");
+
+// Store each pledger's outstanding contribution - key: (campaign id, pledger wallet)
+pub const CAMPAIGN_PLEDGES: Map<(u64, &Addr), Uint128> = Map::new("campaign_pledges");
+
+// Contract-wide settings managed by config admins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct Config {
+    pub escrow_timeout_seconds: u64, // How long escrowed tips wait before the sender can reclaim
+    pub unregistered_tip_expiry_seconds: u64, // How long tips to unregistered usernames wait before the sender can reclaim
+    pub keeper_reward_bps: u64,     // Keeper reward per scheduled tip execution, in basis points of the tip
+    pub refund_request_window_seconds: u64, // How long after a tip its sender may ask for a refund
+    pub platform_fee_bps: u64,      // Fee kept from delivered tips, stream withdrawals, subscription claims and campaign payouts, in basis points
+    pub referral_share_bps: u64,    // Share of the platform fee paid to the recipient's referrer, in basis points
+    pub referral_period_seconds: u64, // How long after registration a referrer earns from a profile
+    pub receipt_nft_contract: Option<Addr>, // cw721-base contract that mints tip receipts
+    pub profile_nft_contract: Option<Addr>, // cw721-base contract holding one token per username
+    pub marketplace_fee_bps: u64,   // Fee kept from username sales, in basis points
+    pub registration_fee: Option<Coin>, // Fee per registration period for names of 7+ characters
+    pub registration_period_seconds: u64, // How long a registration or renewal lasts; 0 never lapses
+    pub registration_grace_seconds: u64, // How long after expiry only the owner can renew
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            escrow_timeout_seconds: 30 * 24 * 60 * 60,
+            unregistered_tip_expiry_seconds: 90 * 24 * 60 * 60,
+            keeper_reward_bps: 50,
+            refund_request_window_seconds: 24 * 60 * 60,
+            platform_fee_bps: 0,
+            referral_share_bps: 2_000,
+            referral_period_seconds: 180 * 24 * 60 * 60,
+            receipt_nft_contract: None,
+            profile_nft_contract: None,
+            marketplace_fee_bps: 250,
+            registration_fee: None,
+            registration_period_seconds: 365 * 24 * 60 * 60,
+            registration_grace_seconds: 30 * 24 * 60 * 60,
+        }
+    }
+}
+
+// Store the contract settings; missing means defaults
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Store escrowed tip funds by tip key
+pub const ESCROWED_TIPS: Map<&str, EscrowedTip> = Map::new("escrowed_tips");
+
+// Index of pending escrowed tips by recipient - key: (username, tip key)
+pub const PENDING_TIPS: Map<(&str, &str), bool> = Map::new("pending_tips");
+
+// Funded tip waiting for its recipient username to be registered
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UnregisteredNameTip {
+    pub id: u64,                    // Identifier of the held tip
+    pub sender: Addr,               // Wallet that attached the funds
+    pub from_username: String,      // Username of the sender
+    pub to_username: String,        // Username the tip is waiting for
+    pub funds: Coin,                // Held funds
+    pub message: Option<String>,    // Optional message with the tip
+    pub created_at: Timestamp,      // When the tip was sent
+    pub expires_at: Timestamp,      // When the sender may take the funds back
+}
+
+// Store tips held for unregistered usernames - key: (username, tip id)
+pub const UNREGISTERED_NAME_TIPS: Map<(&str, u64), UnregisteredNameTip> = Map::new("unregistered_name_tips");
+
+// Store the last issued held tip id
+pub const UNREGISTERED_NAME_TIP_SEQ: Item<u64> = Item::new("unregistered_name_tip_seq");
+
+// Index of referred profiles by referrer - key: (referrer username, referred username)
+pub const REFERRALS: Map<(&str, &str), bool> = Map::new("referrals");
+
+// Store referral fee shares paid to each referrer, per denom
+pub const REFERRAL_EARNINGS: Map<&str, Vec<Coin>> = Map::new("referral_earnings");
+
+// Store platform fees held by the contract until a treasurer withdraws them
+pub const PLATFORM_FEES: Item<Vec<Coin>> = Item::new("platform_fees");
+
+// Store the minimum tip for which each creator's supporters receive a receipt NFT
+pub const RECEIPT_THRESHOLDS: Map<&str, Coin> = Map::new("receipt_thresholds");
+
+// Store the receipt NFT minted for a tip - key: tip id, value: token id
+pub const TIP_RECEIPTS: Map<&str, String> = Map::new("tip_receipts");
+
+// Store the last issued receipt token number
+pub const RECEIPT_SEQ: Item<u64> = Item::new("receipt_seq");
+
+// Store receipts being minted, in submessage order, until each cw721 reply arrives - value: (tip id, token id)
+pub const PENDING_RECEIPTS: Deque<(String, String)> = Deque::new("pending_receipts");
+
+// Index of usernames minted as profile NFTs (token id = username)
+pub const PROFILE_NFTS: Map<&str, bool> = Map::new("profile_nfts");
+
+// Times a username's profile NFT was reissued after a lapse left the old token with its holder.
+// Reissued tokens use the id "username#n"; the first token keeps the bare username.
+pub const PROFILE_NFT_REISSUES: Map<&str, u64> = Map::new("profile_nft_reissues");
+
+// Username offered for sale at a fixed price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Listing {
+    pub username: String,           // Username for sale
+    pub seller: Addr,               // Wallet that controls the profile
+    pub price: Coin,                // Asking price
+    pub keep_profile_data: bool,    // Whether the buyer gets the profile as is or reset
+    pub nft_escrowed: bool,         // Whether the contract holds the profile NFT for the sale
+    pub listed_at: Timestamp,       // When the username was listed
+}
+
+// Store active listings by username
+pub const LISTINGS: Map<&str, Listing> = Map::new("listings");
+
+// Escrowed offer to buy a username
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Offer {
+    pub id: u64,                    // Offer identifier
+    pub username: String,           // Username the offer is for
+    pub buyer: Addr,                // Wallet that escrowed the funds
+    pub price: Coin,                // Escrowed amount
+    pub created_at: Timestamp,      // When the offer was made
+}
+
+// Store open offers - key: (username, offer id)
+pub const OFFERS: Map<(&str, u64), Offer> = Map::new("offers");
+
+// Store the last issued offer id
+pub const OFFER_SEQ: Item<u64> = Item::new("offer_seq");
+
+// Registration that lapsed and was replaced, kept with its tip history
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ArchivedProfile {
+    pub id: u64,                    // Archive identifier
+    pub profile: UserProfile,       // Profile as it was when the registration lapsed
+    pub archived_at: Timestamp,     // When the username was registered again
+    pub tips_sent: Vec<(String, String)>, // Tips sent under the archived registration
+    pub tips_received: Vec<(String, String)>, // Tips received under the archived registration
+}
+
+// Store archived registrations - key: (username, archive id)
+pub const ARCHIVED_PROFILES: Map<(&str, u64), ArchivedProfile> = Map::new("archived_profiles");
+
+// Store the last issued archive id
+pub const ARCHIVE_SEQ: Item<u64> = Item::new("archive_seq");
+
+// Store each wallet's deposited balance, spent by delegates tipping on its behalf
+pub const DEPOSITS: Map<&Addr, Vec<Coin>> = Map::new("deposits");
+
+// Permission for a delegate to tip from a fan's deposited balance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TipAllowance {
+    pub delegate: Addr,             // Wallet allowed to tip on the fan's behalf
+    pub spend_limit: Vec<Coin>,     // Most the delegate may spend per period, per denom
+    pub period_seconds: u64,        // Length of a spending period
+    pub allowed_recipients: Option<Vec<String>>, // Usernames the delegate may tip; None allows any
+    pub expires_at: Option<Timestamp>, // When the allowance stops working
+    pub period_start: Timestamp,    // Start of the current spending period
+    pub spent: Vec<Coin>,           // Amount spent in the current period
+}
+
+// Store tip allowances - key: (granting wallet, delegate)
+pub const TIP_ALLOWANCES: Map<(&Addr, &Addr), TipAllowance> = Map::new("tip_allowances");